@@ -1,7 +1,11 @@
 use crate::hal::{delay::DelayNs, digital::OutputPin};
 use crate::{
-    hal, mode, register_access::get_errors, AlgorithmResult, BitFlags, Ccs811, Ccs811AppMode,
-    Ccs811Awake, Error, ErrorAwake, InterruptMode, MeasurementMode, ModeChangeError, Register,
+    blocking::{block_with_timeout, data_poll_interval_ms},
+    hal, mode,
+    register_access::{check_app_mode, get_errors},
+    AlgorithmResult, BitFlags, Ccs811, Ccs811AppMode, Ccs811Awake, Ccs811Config, Ccs811Device,
+    Clock, Eco2Thresholds, Error, ErrorAwake, InterruptMode, MeasurementMode, ModeChangeError,
+    Register, StatusCheck, TimedResult,
 };
 
 impl<I2C, E> Ccs811AppMode for Ccs811Awake<I2C, mode::App>
@@ -13,21 +17,17 @@ where
     type BootModeType = Ccs811Awake<I2C, mode::Boot>;
 
     fn set_mode(&mut self, mode: MeasurementMode) -> Result<(), Self::Error> {
-        let idle_mode = self.meas_mode_reg & 0b0000_1100;
-        let meas_mode = match mode {
-            MeasurementMode::Idle => idle_mode,
-            MeasurementMode::ConstantPower1s => idle_mode | (1 << 4),
-            MeasurementMode::PulseHeating10s => idle_mode | (2 << 4),
-            MeasurementMode::LowPowerPulseHeating60s => idle_mode | (3 << 4),
-            MeasurementMode::ConstantPower250ms => idle_mode | (4 << 4),
-        };
-        self.write_register_1byte(Register::MEAS_MODE, meas_mode)?;
-        self.meas_mode_reg = meas_mode;
+        let meas_mode = (self.meas_mode_reg.unwrap_or(0) & 0b0000_1100) | get_mode_bits(mode);
+        self.write_setting(&[Register::MEAS_MODE, meas_mode])?;
+        self.meas_mode_reg = Some(meas_mode);
+        self.last_sample_ms = None;
+        self.update_config(|config| config.mode = mode);
         Ok(())
     }
 
     fn has_data_ready(&mut self) -> Result<bool, Self::Error> {
         let status = self.read_status()?;
+        check_app_mode(status)?;
         Ok((status & BitFlags::DATA_READY) != 0)
     }
 
@@ -42,6 +42,7 @@ where
             .write_read(self.address, &[Register::ALG_RESULT_DATA], &mut data)
             .map_err(ErrorAwake::I2C)?;
         let status = data[4];
+        check_app_mode(status)?;
         if (status & BitFlags::ERROR) != 0 {
             get_errors(data[5]).map_err(ErrorAwake::Device)?;
         } else if (status & BitFlags::DATA_READY) == 0 {
@@ -56,31 +57,45 @@ where
         })
     }
 
-    #[allow(clippy::manual_range_contains)] // avoid creating range with exact floats
+    fn data_timed<C: Clock>(&mut self, clock: &C) -> nb::Result<TimedResult, Self::Error> {
+        let result = self.data()?;
+        let timestamp_ms = clock.now_ms();
+        let since_previous_ms = self
+            .last_sample_ms
+            .map(|last| timestamp_ms.wrapping_sub(last));
+        self.last_sample_ms = Some(timestamp_ms);
+        Ok(TimedResult {
+            result,
+            timestamp_ms,
+            since_previous_ms,
+        })
+    }
+
+    fn read_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<AlgorithmResult, Self::Error> {
+        let meas_mode = self.meas_mode()?;
+        let (interval, timeout_ms) = match data_poll_interval_ms(meas_mode)? {
+            Some(interval) => (interval, timeout_ms),
+            None => (0, 0),
+        };
+        block_with_timeout(delay, interval, timeout_ms, ErrorAwake::Timeout, || {
+            self.data()
+        })
+    }
+
     fn set_environment(
         &mut self,
         humidity_percentage: f32,
         temperature_celsius: f32,
     ) -> Result<(), Self::Error> {
-        if humidity_percentage < 0.0
-            || humidity_percentage > 100.0
-            || temperature_celsius > 254.998_05
-        {
-            return Err(ErrorAwake::InvalidInputData);
-        }
-        let raw_humidity = get_raw_humidity(humidity_percentage);
-        let raw_temp = get_raw_temperature(temperature_celsius);
-        let raw = [
-            Register::ENV_DATA,
-            raw_humidity.0,
-            raw_humidity.1,
-            raw_temp.0,
-            raw_temp.1,
-        ];
-        self.i2c
-            .write(self.address, &raw)
-            .map_err(ErrorAwake::I2C)?;
-        self.check_status_error()
+        self.write_environment(humidity_percentage, temperature_celsius)?;
+        self.update_config(|config| {
+            config.environment = Some((humidity_percentage, temperature_celsius))
+        });
+        Ok(())
     }
 
     fn baseline(&mut self) -> Result<[u8; 2], Self::Error> {
@@ -88,13 +103,9 @@ where
     }
 
     fn set_baseline(&mut self, baseline: [u8; 2]) -> Result<(), Self::Error> {
-        self.i2c
-            .write(
-                self.address,
-                &[Register::BASELINE, baseline[0], baseline[1]],
-            )
-            .map_err(ErrorAwake::I2C)?;
-        self.check_status_error()
+        self.write_baseline(baseline)?;
+        self.update_config(|config| config.baseline = Some(baseline));
+        Ok(())
     }
 
     fn set_eco2_thresholds(
@@ -102,40 +113,83 @@ where
         low_to_medium: u16,
         medium_to_high: u16,
     ) -> Result<(), Self::Error> {
-        self.i2c
-            .write(
-                self.address,
-                &[
-                    Register::THRESHOLDS,
-                    (low_to_medium >> 8) as u8,
-                    low_to_medium as u8,
-                    (medium_to_high >> 8) as u8,
-                    medium_to_high as u8,
-                ],
-            )
-            .map_err(ErrorAwake::I2C)?;
-        self.check_status_error()
+        self.write_eco2_thresholds(low_to_medium, medium_to_high)?;
+        self.update_config(|config| {
+            config.eco2_thresholds = Eco2Thresholds::new(low_to_medium, medium_to_high)
+        });
+        Ok(())
     }
 
     fn set_interrupt_mode(&mut self, mode: InterruptMode) -> Result<(), Self::Error> {
-        let int_mask = match mode {
-            InterruptMode::Disabled => 0,
-            InterruptMode::OnDataReady => BitFlags::INTERRUPT,
-            InterruptMode::OnThresholdCrossed => BitFlags::INTERRUPT | BitFlags::THRESH,
-        };
-        let meas_mode = (self.meas_mode_reg & (0b111 << 4)) | int_mask;
-        self.write_register_1byte(Register::MEAS_MODE, meas_mode)?;
-        self.meas_mode_reg = meas_mode;
+        let meas_mode = (self.meas_mode_reg.unwrap_or(0) & (0b111 << 4)) | get_interrupt_bits(mode);
+        self.write_setting(&[Register::MEAS_MODE, meas_mode])?;
+        self.meas_mode_reg = Some(meas_mode);
+        self.update_config(|config| config.interrupt_mode = mode);
         Ok(())
     }
 
+    fn apply(&mut self, config: &Ccs811Config) -> Result<(), Self::Error> {
+        if let Some((humidity, temperature)) = config.environment {
+            check_environment(humidity, temperature)?;
+        }
+        if let Some(thresholds) = config.eco2_thresholds {
+            self.write_eco2_thresholds(thresholds.low_to_medium(), thresholds.medium_to_high())?;
+        }
+        if let Some((humidity, temperature)) = config.environment {
+            self.write_environment(humidity, temperature)?;
+        }
+        let meas_mode = get_mode_bits(config.mode) | get_interrupt_bits(config.interrupt_mode);
+        self.write_setting(&[Register::MEAS_MODE, meas_mode])?;
+        self.meas_mode_reg = Some(meas_mode);
+        self.last_sample_ms = None;
+        if let Some(baseline) = config.baseline {
+            self.write_baseline(baseline)?;
+        }
+        self.config = Some(*config);
+        Ok(())
+    }
+
+    fn applied_config(&self) -> Option<Ccs811Config> {
+        self.config
+    }
+
+    fn reapply(&mut self) -> Result<(), Self::Error> {
+        match self.config {
+            Some(config) => self.apply(&config),
+            None => Ok(()),
+        }
+    }
+
     // Note: is_verifying is false after a reset
     fn software_reset(mut self) -> Result<Self::BootModeType, Self::ModeChangeError> {
         match self.write_sw_reset() {
             Err(e) => Err(ModeChangeError::new(self, e)),
-            Ok(_) => Ok(Ccs811Awake::create(self.i2c, self.address)),
+            Ok(_) => Ok(self.into_mode()),
         }
     }
+
+    fn restart_application(&mut self) -> Result<(), Self::Error> {
+        if !self.has_valid_app()? {
+            return Err(ErrorAwake::NoValidApp);
+        }
+        self.write_register_no_data(Register::APP_START)?;
+        self.meas_mode_reg = None;
+        self.last_sample_ms = None;
+        Ok(())
+    }
+}
+
+#[allow(clippy::manual_range_contains)] // avoid creating range with exact floats
+fn check_environment<E>(
+    humidity_percentage: f32,
+    temperature_celsius: f32,
+) -> Result<(), ErrorAwake<E>> {
+    if humidity_percentage < 0.0 || humidity_percentage > 100.0 || temperature_celsius > 254.998_05
+    {
+        Err(ErrorAwake::InvalidInputData)
+    } else {
+        Ok(())
+    }
 }
 
 fn get_raw_humidity(humidity_percentage: f32) -> (u8, u8) {
@@ -158,11 +212,131 @@ fn get_raw_environment_data(value: f32) -> (u8, u8) {
     (main | (((rest & (1 << 8)) >> 8) as u8), rest as u8)
 }
 
+impl<I2C, E> Ccs811Awake<I2C, mode::App>
+where
+    I2C: hal::i2c::I2c<Error = E>,
+{
+    /// Get the MEAS_MODE register value, reading it from the device if it
+    /// is unknown since the application started.
+    pub(crate) fn meas_mode(&mut self) -> Result<u8, ErrorAwake<E>> {
+        match self.meas_mode_reg {
+            Some(meas_mode) => Ok(meas_mode),
+            None => {
+                let meas_mode = self.read_register_1byte(Register::MEAS_MODE)?;
+                self.meas_mode_reg = Some(meas_mode);
+                Ok(meas_mode)
+            }
+        }
+    }
+
+    fn write_environment(
+        &mut self,
+        humidity_percentage: f32,
+        temperature_celsius: f32,
+    ) -> Result<(), ErrorAwake<E>> {
+        check_environment(humidity_percentage, temperature_celsius)?;
+        let raw_humidity = get_raw_humidity(humidity_percentage);
+        let raw_temp = get_raw_temperature(temperature_celsius);
+        self.write_setting(&[
+            Register::ENV_DATA,
+            raw_humidity.0,
+            raw_humidity.1,
+            raw_temp.0,
+            raw_temp.1,
+        ])
+    }
+
+    pub(crate) fn write_baseline(&mut self, baseline: [u8; 2]) -> Result<(), ErrorAwake<E>> {
+        self.write_setting(&[Register::BASELINE, baseline[0], baseline[1]])
+    }
+
+    fn write_eco2_thresholds(
+        &mut self,
+        low_to_medium: u16,
+        medium_to_high: u16,
+    ) -> Result<(), ErrorAwake<E>> {
+        self.write_setting(&[
+            Register::THRESHOLDS,
+            (low_to_medium >> 8) as u8,
+            low_to_medium as u8,
+            (medium_to_high >> 8) as u8,
+            medium_to_high as u8,
+        ])
+    }
+
+    /// Write a setting and check the status, including the FW_MODE bit
+    /// since the setting is lost if the device returned to boot mode.
+    fn write_setting(&mut self, data: &[u8]) -> Result<(), ErrorAwake<E>> {
+        self.i2c
+            .write(self.address, data)
+            .map_err(ErrorAwake::I2C)?;
+        match self.status_check {
+            StatusCheck::Always => check_app_mode(self.read_status()?),
+            StatusCheck::Deferred | StatusCheck::Never => Ok(()),
+        }
+    }
+}
+
+impl<I2C, MODE> Ccs811Awake<I2C, MODE> {
+    /// Update the configuration record, creating it from the current
+    /// measurement and interrupt modes if no configuration was applied.
+    fn update_config<F: FnOnce(&mut Ccs811Config)>(&mut self, f: F) {
+        let meas_mode = self.meas_mode_reg.unwrap_or(0);
+        let config = self.config.get_or_insert_with(|| Ccs811Config {
+            mode: get_mode(meas_mode),
+            interrupt_mode: get_interrupt_mode(meas_mode),
+            eco2_thresholds: None,
+            environment: None,
+            baseline: None,
+        });
+        f(config);
+    }
+}
+
+fn get_mode_bits(mode: MeasurementMode) -> u8 {
+    match mode {
+        MeasurementMode::Idle => 0,
+        MeasurementMode::ConstantPower1s => 1 << 4,
+        MeasurementMode::PulseHeating10s => 2 << 4,
+        MeasurementMode::LowPowerPulseHeating60s => 3 << 4,
+        MeasurementMode::ConstantPower250ms => 4 << 4,
+    }
+}
+
+fn get_mode(meas_mode: u8) -> MeasurementMode {
+    match (meas_mode >> 4) & 0b111 {
+        1 => MeasurementMode::ConstantPower1s,
+        2 => MeasurementMode::PulseHeating10s,
+        3 => MeasurementMode::LowPowerPulseHeating60s,
+        4 => MeasurementMode::ConstantPower250ms,
+        _ => MeasurementMode::Idle,
+    }
+}
+
+fn get_interrupt_mode(meas_mode: u8) -> InterruptMode {
+    if (meas_mode & BitFlags::INTERRUPT) == 0 {
+        InterruptMode::Disabled
+    } else if (meas_mode & BitFlags::THRESH) == 0 {
+        InterruptMode::OnDataReady
+    } else {
+        InterruptMode::OnThresholdCrossed
+    }
+}
+
+fn get_interrupt_bits(mode: InterruptMode) -> u8 {
+    match mode {
+        InterruptMode::Disabled => 0,
+        InterruptMode::OnDataReady => BitFlags::INTERRUPT,
+        InterruptMode::OnThresholdCrossed => BitFlags::INTERRUPT | BitFlags::THRESH,
+    }
+}
+
 fn handle_raw_data(data0: u8, data1: u8) -> (u8, u16) {
     (data1 >> 2, u16::from(data0) | (u16::from(data1 & 0x3) << 8))
 }
 
-impl<I2C, CommE, PinE, NWAKE, WAKEDELAY> Ccs811AppMode for Ccs811<I2C, NWAKE, WAKEDELAY, mode::App>
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, NRESET> Ccs811AppMode
+    for Ccs811<I2C, NWAKE, WAKEDELAY, mode::App, NRESET>
 where
     I2C: hal::i2c::I2c<Error = CommE>,
     NWAKE: OutputPin<Error = PinE>,
@@ -170,7 +344,7 @@ where
 {
     type Error = Error<CommE, PinE>;
     type ModeChangeError = ModeChangeError<Error<CommE, PinE>, Self>;
-    type BootModeType = Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot>;
+    type BootModeType = Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot, NRESET>;
 
     fn set_mode(&mut self, mode: MeasurementMode) -> Result<(), Self::Error> {
         self.on_awaken(|s| s.dev.set_mode(mode))
@@ -188,6 +362,23 @@ where
         self.on_awaken_nb(|s| s.dev.data())
     }
 
+    fn data_timed<C: Clock>(&mut self, clock: &C) -> nb::Result<TimedResult, Self::Error> {
+        self.on_awaken_nb(|s| s.dev.data_timed(clock))
+    }
+
+    fn read_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<AlgorithmResult, Self::Error> {
+        let meas_mode = self.on_awaken(|s| s.dev.meas_mode())?;
+        let (interval, timeout_ms) = match data_poll_interval_ms(meas_mode)? {
+            Some(interval) => (interval, timeout_ms),
+            None => (0, 0),
+        };
+        block_with_timeout(delay, interval, timeout_ms, Error::Timeout, || self.data())
+    }
+
     fn baseline(&mut self) -> Result<[u8; 2], Self::Error> {
         self.on_awaken(|s| s.dev.baseline())
     }
@@ -219,6 +410,22 @@ where
         self.on_awaken(|s| s.dev.set_interrupt_mode(mode))
     }
 
+    fn apply(&mut self, config: &Ccs811Config) -> Result<(), Self::Error> {
+        self.on_awaken(|s| s.dev.apply(config))
+    }
+
+    fn applied_config(&self) -> Option<Ccs811Config> {
+        self.dev.applied_config()
+    }
+
+    fn reapply(&mut self) -> Result<(), Self::Error> {
+        self.on_awaken(|s| s.dev.reapply())
+    }
+
+    fn restart_application(&mut self) -> Result<(), Self::Error> {
+        self.on_awaken(|s| s.dev.restart_application())
+    }
+
     fn software_reset(self) -> Result<Self::BootModeType, Self::ModeChangeError> {
         self.wrap_mode_change(|s| s.software_reset())
     }
@@ -0,0 +1,151 @@
+use crate::{Ccs811AppMode, Clock, DeviceErrors};
+
+/// Configuration of a `BaselineScheduler`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BaselineScheduleConfig {
+    /// Time (ms) the sensor must run before the first baseline is saved.
+    /// Default: 48 hours.
+    pub burn_in_ms: u32,
+    /// Time (ms) the sensor must run after power-up or a reset before the
+    /// saved baseline is restored. Default: 20 minutes.
+    pub warm_up_ms: u32,
+    /// Time (ms) between baseline saves after the burn-in. Default: 24 hours.
+    pub save_period_ms: u32,
+}
+
+impl Default for BaselineScheduleConfig {
+    fn default() -> Self {
+        BaselineScheduleConfig {
+            burn_in_ms: 48 * 60 * 60 * 1000,
+            warm_up_ms: 20 * 60 * 1000,
+            save_period_ms: 24 * 60 * 60 * 1000,
+        }
+    }
+}
+
+/// Baseline event reported by `BaselineScheduler::poll()`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BaselineEvent {
+    /// The baseline was read from the device and should be written to the
+    /// application storage.
+    Save([u8; 2]),
+    /// The saved baseline was restored into the device.
+    Restored([u8; 2]),
+}
+
+/// Scheduler saving and restoring the baseline following the datasheet
+/// guidance.
+///
+/// The first baseline is saved after the burn-in and then periodically.
+/// A saved baseline is only restored after the warm-up following power-up
+/// or a reset, since restoring it earlier hurts the accuracy.
+/// No baseline is saved while the device reports errors.
+///
+/// `poll()` should be called periodically (e.g. after every reading).
+/// The clock may wrap around.
+#[derive(Debug)]
+pub struct BaselineScheduler<C> {
+    clock: C,
+    config: BaselineScheduleConfig,
+    started_ms: u32,
+    last_save_ms: u32,
+    saved: Option<[u8; 2]>,
+    restored: bool,
+    burned_in: bool,
+    errors_active: bool,
+}
+
+impl<C: Clock> BaselineScheduler<C> {
+    /// Create a new scheduler for a device which was just started.
+    ///
+    /// `saved` is the baseline read from the application storage, if any.
+    /// A sensor with a saved baseline is considered burned in.
+    pub fn new(clock: C, config: BaselineScheduleConfig, saved: Option<[u8; 2]>) -> Self {
+        let now_ms = clock.now_ms();
+        BaselineScheduler {
+            clock,
+            config,
+            started_ms: now_ms,
+            last_save_ms: now_ms,
+            saved,
+            restored: false,
+            burned_in: saved.is_some(),
+            errors_active: false,
+        }
+    }
+
+    /// Destroy the scheduler and return the clock.
+    pub fn destroy(self) -> C {
+        self.clock
+    }
+
+    /// Get the last saved baseline, if any.
+    pub fn saved_baseline(&self) -> Option<[u8; 2]> {
+        self.saved
+    }
+
+    /// Whether the sensor is still in the warm-up after power-up or a reset.
+    pub fn is_warming_up(&self) -> bool {
+        self.clock.now_ms().wrapping_sub(self.started_ms) < self.config.warm_up_ms
+    }
+
+    /// Whether the sensor is burned in.
+    pub fn is_burned_in(&self) -> bool {
+        self.burned_in
+    }
+
+    /// Start over the warm-up after the device was powered up or reset.
+    ///
+    /// The saved baseline is restored again after the warm-up.
+    pub fn restart(&mut self) {
+        let now_ms = self.clock.now_ms();
+        self.started_ms = now_ms;
+        self.restored = false;
+        if self.burned_in {
+            self.last_save_ms = now_ms;
+        }
+    }
+
+    /// Feed the errors reported by the device.
+    ///
+    /// Saving is refused until this is called again without errors.
+    pub fn on_device_errors(&mut self, errors: &DeviceErrors) {
+        self.errors_active = *errors != DeviceErrors::default();
+    }
+
+    /// Save or restore the baseline if due.
+    ///
+    /// Returns the event which occurred, if any. If restoring the baseline
+    /// fails, it is attempted again on the next call and no baseline is
+    /// saved until it succeeds.
+    pub fn poll<DEV: Ccs811AppMode>(
+        &mut self,
+        dev: &mut DEV,
+    ) -> Result<Option<BaselineEvent>, DEV::Error> {
+        if self.is_warming_up() {
+            return Ok(None);
+        }
+        if !self.restored {
+            if let Some(baseline) = self.saved {
+                dev.set_baseline(baseline)?;
+                self.restored = true;
+                return Ok(Some(BaselineEvent::Restored(baseline)));
+            }
+            self.restored = true;
+        }
+        let now_ms = self.clock.now_ms();
+        let period_ms = if self.burned_in {
+            self.config.save_period_ms
+        } else {
+            self.config.burn_in_ms
+        };
+        if self.errors_active || now_ms.wrapping_sub(self.last_save_ms) < period_ms {
+            return Ok(None);
+        }
+        let baseline = dev.baseline()?;
+        self.saved = Some(baseline);
+        self.last_save_ms = now_ms;
+        self.burned_in = true;
+        Ok(Some(BaselineEvent::Save(baseline)))
+    }
+}
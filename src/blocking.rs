@@ -0,0 +1,54 @@
+use crate::hal::delay::DelayNs;
+use crate::ErrorAwake;
+
+/// Poll interval while waiting for the firmware erase to complete.
+pub(crate) const ERASE_POLL_INTERVAL_MS: u32 = 500;
+/// Poll interval while waiting for the firmware verification to complete.
+pub(crate) const VERIFY_POLL_INTERVAL_MS: u32 = 70;
+
+/// Get the data poll interval for the MEAS_MODE register value.
+///
+/// This is a tenth of the sample period. `None` in idle mode.
+/// Returns `InvalidInputData` in `ConstantPower250ms` mode since the
+/// algorithm results are not updated in that mode.
+pub(crate) fn data_poll_interval_ms<E>(meas_mode_reg: u8) -> Result<Option<u32>, ErrorAwake<E>> {
+    match (meas_mode_reg >> 4) & 0b111 {
+        1 => Ok(Some(100)),
+        2 => Ok(Some(1_000)),
+        3 => Ok(Some(6_000)),
+        4 => Err(ErrorAwake::InvalidInputData),
+        _ => Ok(None),
+    }
+}
+
+/// Call `f` until it completes, sleeping `interval_ms` in between.
+///
+/// Returns `timeout` once `f` did not complete within `timeout_ms`.
+/// Only the time spent sleeping is accounted.
+pub(crate) fn block_with_timeout<T, E, D, F>(
+    delay: &mut D,
+    interval_ms: u32,
+    timeout_ms: u32,
+    timeout: E,
+    mut f: F,
+) -> Result<T, E>
+where
+    D: DelayNs,
+    F: FnMut() -> nb::Result<T, E>,
+{
+    let mut elapsed = 0;
+    loop {
+        match f() {
+            Ok(v) => return Ok(v),
+            Err(nb::Error::Other(e)) => return Err(e),
+            Err(nb::Error::WouldBlock) => {
+                if elapsed >= timeout_ms {
+                    return Err(timeout);
+                }
+                let wait = interval_ms.min(timeout_ms - elapsed);
+                delay.delay_ms(wait);
+                elapsed += wait;
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use crate::hal::{delay::DelayNs, digital::OutputPin};
 use crate::{
+    blocking::{block_with_timeout, ERASE_POLL_INTERVAL_MS, VERIFY_POLL_INTERVAL_MS},
     hal, mode, ActionInProgress, BitFlags, Ccs811, Ccs811Awake, Ccs811BootMode, Ccs811Device,
     Error, ErrorAwake, ModeChangeError, Register,
 };
@@ -21,7 +22,7 @@ where
                 } else {
                     match self.write_register_no_data(Register::APP_START) {
                         Err(e) => Err(ModeChangeError::new(self, e)),
-                        Ok(_) => Ok(Ccs811Awake::create(self.i2c, self.address)),
+                        Ok(_) => Ok(self.into_mode()),
                     }
                 }
             }
@@ -53,6 +54,20 @@ where
         }
     }
 
+    fn verify_application_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<(), Self::Error> {
+        block_with_timeout(
+            delay,
+            VERIFY_POLL_INTERVAL_MS,
+            timeout_ms,
+            ErrorAwake::Timeout,
+            || self.verify_application(),
+        )
+    }
+
     fn erase_application(&mut self) -> nb::Result<(), Self::Error> {
         let status = self.read_status().map_err(nb::Error::Other)?;
         let erased = (status & BitFlags::APP_ERASE) != 0;
@@ -78,6 +93,20 @@ where
         }
     }
 
+    fn erase_application_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<(), Self::Error> {
+        block_with_timeout(
+            delay,
+            ERASE_POLL_INTERVAL_MS,
+            timeout_ms,
+            ErrorAwake::Timeout,
+            || self.erase_application(),
+        )
+    }
+
     fn download_application<D: DelayNs>(
         &mut self,
         bin: &[u8],
@@ -129,8 +158,8 @@ where
     }
 }
 
-impl<I2C, CommE, PinE, NWAKE, WAKEDELAY> Ccs811BootMode
-    for Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot>
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, NRESET> Ccs811BootMode
+    for Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot, NRESET>
 where
     I2C: hal::i2c::I2c<Error = CommE>,
     NWAKE: OutputPin<Error = PinE>,
@@ -138,7 +167,7 @@ where
 {
     type Error = Error<CommE, PinE>;
     type ModeChangeError = ModeChangeError<Self::Error, Self>;
-    type TargetType = Ccs811<I2C, NWAKE, WAKEDELAY, mode::App>;
+    type TargetType = Ccs811<I2C, NWAKE, WAKEDELAY, mode::App, NRESET>;
 
     fn start_application(self) -> Result<Self::TargetType, Self::ModeChangeError> {
         self.wrap_mode_change(|s| s.start_application())
@@ -152,6 +181,34 @@ where
         self.on_awaken_nb(|s| s.dev.erase_application())
     }
 
+    fn verify_application_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<(), Self::Error> {
+        block_with_timeout(
+            delay,
+            VERIFY_POLL_INTERVAL_MS,
+            timeout_ms,
+            Error::Timeout,
+            || self.verify_application(),
+        )
+    }
+
+    fn erase_application_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<(), Self::Error> {
+        block_with_timeout(
+            delay,
+            ERASE_POLL_INTERVAL_MS,
+            timeout_ms,
+            Error::Timeout,
+            || self.erase_application(),
+        )
+    }
+
     fn download_application<D: DelayNs>(
         &mut self,
         bin: &[u8],
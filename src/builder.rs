@@ -0,0 +1,426 @@
+use crate::hal::{
+    delay::DelayNs,
+    digital::{InputPin, OutputPin},
+};
+use crate::{
+    hal, mode, private, Ccs811, Ccs811AppMode, Ccs811Awake, Ccs811BootMode, Ccs811Config,
+    Ccs811Device, Eco2Thresholds, Error, ErrorAwake, InterruptMode, MeasurementMode,
+    ModeChangeError, NoPin, SlaveAddr,
+};
+
+/// Builder running the complete bring-up of a CCS811 device.
+///
+/// Starting from power-up, the device is reset, its identity and application
+/// are checked, the application is started and the configuration is applied.
+/// See `Ccs811AppMode::apply()`.
+///
+/// The baseline is not written since restoring it right after power-up hurts
+/// the accuracy. Give the saved baseline to `BaselineScheduler` instead,
+/// which restores it after the warm-up.
+///
+/// If an nWAKE pin is provided the result is a `Ccs811` device.
+/// Otherwise the result is a `Ccs811Awake` device.
+/// An nRESET pin can only be provided together with an nWAKE pin. In that
+/// case the device is reset through it instead of with a software reset.
+///
+/// An nINT pin can be provided as well. It is not used by the driver but it
+/// is handed back together with the device. See `with_interrupt_pin()`.
+#[derive(Debug)]
+pub struct Ccs811Builder<I2C, NWAKE = NoPin, WAKEDELAY = NoPin, NRESET = NoPin, NINT = NoPin> {
+    i2c: I2C,
+    address: SlaveAddr,
+    n_wake_pin: NWAKE,
+    wake_delay: WAKEDELAY,
+    n_reset_pin: NRESET,
+    n_int_pin: NINT,
+    config: Ccs811Config,
+}
+
+/// Initialization step
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum InitStep {
+    /// Software or hardware reset
+    Reset,
+    /// Identity check. See `Ccs811Device::identify()`.
+    Identify,
+    /// Valid application check
+    ValidApp,
+    /// Application start
+    StartApplication,
+    /// Configuration. See `Ccs811AppMode::apply()`.
+    Configure,
+}
+
+/// Device handed back after a failed initialization.
+#[derive(Debug)]
+pub enum InitDevice<BOOT, APP> {
+    /// The step failed before the application was started.
+    Boot(BOOT),
+    /// The step failed after the application was started.
+    App(APP),
+}
+
+/// Error during the initialization with `Ccs811Builder`.
+///
+/// This allows to retrieve the device in case of an error.
+#[derive(Debug)]
+pub struct InitError<E, BOOT, APP> {
+    /// Step which failed.
+    pub step: InitStep,
+    /// Error occurred.
+    pub error: E,
+    /// Device in the state it was when the step failed.
+    pub dev: InitDevice<BOOT, APP>,
+}
+
+impl<E, BOOT, APP> InitError<E, BOOT, APP> {
+    fn boot(step: InitStep, error: E, dev: BOOT) -> Self {
+        InitError {
+            step,
+            error,
+            dev: InitDevice::Boot(dev),
+        }
+    }
+
+    fn app(step: InitStep, error: E, dev: APP) -> Self {
+        InitError {
+            step,
+            error,
+            dev: InitDevice::App(dev),
+        }
+    }
+}
+
+impl<I2C> Ccs811Builder<I2C> {
+    /// Create a new builder.
+    ///
+    /// By default the device is configured with `Ccs811Config::default()`.
+    pub fn new(i2c: I2C, address: SlaveAddr) -> Self {
+        Ccs811Builder {
+            i2c,
+            address,
+            n_wake_pin: NoPin,
+            wake_delay: NoPin,
+            n_reset_pin: NoPin,
+            n_int_pin: NoPin,
+            config: Ccs811Config::default(),
+        }
+    }
+}
+
+impl<I2C, NWAKE, WAKEDELAY, NRESET, NINT> Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET, NINT> {
+    /// Use the nWAKE pin and a delay for waking the device on each operation.
+    pub fn with_wake_pin<P, D>(
+        self,
+        n_wake_pin: P,
+        wake_delay: D,
+    ) -> Ccs811Builder<I2C, P, D, NRESET, NINT> {
+        Ccs811Builder {
+            i2c: self.i2c,
+            address: self.address,
+            n_wake_pin,
+            wake_delay,
+            n_reset_pin: self.n_reset_pin,
+            n_int_pin: self.n_int_pin,
+            config: self.config,
+        }
+    }
+
+    /// Use the nRESET pin for resetting the device.
+    ///
+    /// This requires the nWAKE pin to be provided as well. See `with_wake_pin()`.
+    pub fn with_reset_pin<P>(
+        self,
+        n_reset_pin: P,
+    ) -> Ccs811Builder<I2C, NWAKE, WAKEDELAY, P, NINT> {
+        Ccs811Builder {
+            i2c: self.i2c,
+            address: self.address,
+            n_wake_pin: self.n_wake_pin,
+            wake_delay: self.wake_delay,
+            n_reset_pin,
+            n_int_pin: self.n_int_pin,
+            config: self.config,
+        }
+    }
+
+    /// Keep the nINT pin together with the device.
+    ///
+    /// The pin is handed back by `build()` together with the device, also if
+    /// the initialization fails.
+    pub fn with_interrupt_pin<P>(
+        self,
+        n_int_pin: P,
+    ) -> Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET, P> {
+        Ccs811Builder {
+            i2c: self.i2c,
+            address: self.address,
+            n_wake_pin: self.n_wake_pin,
+            wake_delay: self.wake_delay,
+            n_reset_pin: self.n_reset_pin,
+            n_int_pin,
+            config: self.config,
+        }
+    }
+
+    /// Set the complete configuration to apply.
+    ///
+    /// The baseline of the configuration is ignored. See `BaselineScheduler`.
+    pub fn config(mut self, config: Ccs811Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// Set the measurement mode to configure.
+    pub fn mode(mut self, mode: MeasurementMode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+
+    /// Set the interrupt mode to configure.
+    pub fn interrupt_mode(mut self, mode: InterruptMode) -> Self {
+        self.config.interrupt_mode = mode;
+        self
+    }
+
+    /// Set the eCO2 thresholds to configure.
+    pub fn eco2_thresholds(mut self, thresholds: Eco2Thresholds) -> Self {
+        self.config.eco2_thresholds = Some(thresholds);
+        self
+    }
+
+    /// Set the environment relative humidity and temperature to configure.
+    ///
+    /// See `Ccs811AppMode::set_environment()`.
+    pub fn environment(mut self, humidity_percentage: f32, temperature_celsius: f32) -> Self {
+        self.config.environment = Some((humidity_percentage, temperature_celsius));
+        self
+    }
+}
+
+impl<I2C, E> Ccs811Builder<I2C>
+where
+    I2C: hal::i2c::I2c<Error = E>,
+{
+    /// Run the initialization and return a `Ccs811Awake` device in application mode.
+    ///
+    /// The device is reset with a software reset.
+    #[allow(clippy::type_complexity)]
+    pub fn build<D: DelayNs>(
+        self,
+        delay: &mut D,
+    ) -> Result<
+        Ccs811Awake<I2C, mode::App>,
+        InitError<ErrorAwake<E>, Ccs811Awake<I2C, mode::Boot>, Ccs811Awake<I2C, mode::App>>,
+    > {
+        let mut dev = Ccs811Awake::new(self.i2c, self.address);
+        if let Err(e) = Ccs811BootMode::software_reset(&mut dev) {
+            return Err(InitError::boot(InitStep::Reset, e, dev));
+        }
+        delay.delay_ms(2);
+        configure(&self.config, dev, delay)
+    }
+}
+
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY> Ccs811Builder<I2C, NWAKE, WAKEDELAY>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+{
+    /// Run the initialization and return a `Ccs811` device in application mode.
+    ///
+    /// The device is reset with a software reset.
+    #[allow(clippy::type_complexity)]
+    pub fn build<D: DelayNs>(
+        self,
+        delay: &mut D,
+    ) -> Result<
+        Ccs811<I2C, NWAKE, WAKEDELAY, mode::App>,
+        InitError<
+            Error<CommE, PinE>,
+            Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot>,
+            Ccs811<I2C, NWAKE, WAKEDELAY, mode::App>,
+        >,
+    > {
+        let mut dev = Ccs811::new(self.i2c, self.address, self.n_wake_pin, self.wake_delay);
+        if let Err(e) = Ccs811BootMode::software_reset(&mut dev) {
+            return Err(InitError::boot(InitStep::Reset, e, dev));
+        }
+        delay.delay_ms(2);
+        configure(&self.config, dev, delay)
+    }
+}
+
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, NRESET> Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+    NRESET: OutputPin<Error = PinE>,
+{
+    /// Run the initialization and return a `Ccs811` device in application mode.
+    ///
+    /// The device is reset through the nRESET pin.
+    #[allow(clippy::type_complexity)]
+    pub fn build<D: DelayNs>(
+        self,
+        delay: &mut D,
+    ) -> Result<
+        Ccs811<I2C, NWAKE, WAKEDELAY, mode::App, NRESET>,
+        InitError<
+            Error<CommE, PinE>,
+            Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot, NRESET>,
+            Ccs811<I2C, NWAKE, WAKEDELAY, mode::App, NRESET>,
+        >,
+    > {
+        let dev = Ccs811::new_with_reset_pin(
+            self.i2c,
+            self.address,
+            self.n_wake_pin,
+            self.n_reset_pin,
+            self.wake_delay,
+        );
+        let dev = match dev.hardware_reset() {
+            Ok(dev) => dev,
+            Err(ModeChangeError { dev, error }) => {
+                return Err(InitError::boot(InitStep::Reset, error, dev))
+            }
+        };
+        configure(&self.config, dev, delay)
+    }
+}
+
+impl<I2C, NWAKE, WAKEDELAY, NRESET, NINT> Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET, NINT>
+where
+    NINT: InputPin,
+{
+    /// Run the initialization and return the device in application mode
+    /// together with the nINT pin.
+    ///
+    /// The device is reset and configured as when building without the nINT
+    /// pin. The pin is handed back also if the initialization fails.
+    #[allow(clippy::type_complexity)]
+    pub fn build<D: DelayNs>(
+        self,
+        delay: &mut D,
+    ) -> Result<
+        (
+            <Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET> as private::Build<D>>::Output,
+            NINT,
+        ),
+        (
+            <Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET> as private::Build<D>>::Error,
+            NINT,
+        ),
+    >
+    where
+        Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET>: private::Build<D>,
+    {
+        let builder = Ccs811Builder {
+            i2c: self.i2c,
+            address: self.address,
+            n_wake_pin: self.n_wake_pin,
+            wake_delay: self.wake_delay,
+            n_reset_pin: self.n_reset_pin,
+            n_int_pin: NoPin,
+            config: self.config,
+        };
+        match private::Build::build(builder, delay) {
+            Ok(dev) => Ok((dev, self.n_int_pin)),
+            Err(e) => Err((e, self.n_int_pin)),
+        }
+    }
+}
+
+impl<I2C, E, D> private::Build<D> for Ccs811Builder<I2C>
+where
+    I2C: hal::i2c::I2c<Error = E>,
+    D: DelayNs,
+{
+    type Output = Ccs811Awake<I2C, mode::App>;
+    type Error =
+        InitError<ErrorAwake<E>, Ccs811Awake<I2C, mode::Boot>, Ccs811Awake<I2C, mode::App>>;
+
+    fn build(self, delay: &mut D) -> Result<Self::Output, Self::Error> {
+        self.build(delay)
+    }
+}
+
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, D> private::Build<D>
+    for Ccs811Builder<I2C, NWAKE, WAKEDELAY>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+    D: DelayNs,
+{
+    type Output = Ccs811<I2C, NWAKE, WAKEDELAY, mode::App>;
+    type Error = InitError<
+        Error<CommE, PinE>,
+        Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot>,
+        Ccs811<I2C, NWAKE, WAKEDELAY, mode::App>,
+    >;
+
+    fn build(self, delay: &mut D) -> Result<Self::Output, Self::Error> {
+        self.build(delay)
+    }
+}
+
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, NRESET, D> private::Build<D>
+    for Ccs811Builder<I2C, NWAKE, WAKEDELAY, NRESET>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+    NRESET: OutputPin<Error = PinE>,
+    D: DelayNs,
+{
+    type Output = Ccs811<I2C, NWAKE, WAKEDELAY, mode::App, NRESET>;
+    type Error = InitError<
+        Error<CommE, PinE>,
+        Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot, NRESET>,
+        Ccs811<I2C, NWAKE, WAKEDELAY, mode::App, NRESET>,
+    >;
+
+    fn build(self, delay: &mut D) -> Result<Self::Output, Self::Error> {
+        self.build(delay)
+    }
+}
+
+fn configure<DEV, E, CommE, D>(
+    config: &Ccs811Config,
+    mut dev: DEV,
+    delay: &mut D,
+) -> Result<DEV::TargetType, InitError<E, DEV, DEV::TargetType>>
+where
+    DEV: Ccs811Device<Error = E>
+        + Ccs811BootMode<Error = E, ModeChangeError = ModeChangeError<E, DEV>>,
+    DEV::TargetType: Ccs811AppMode<Error = E>,
+    E: From<ErrorAwake<CommE>>,
+    D: DelayNs,
+{
+    let info = match dev.identify() {
+        Ok(info) => info,
+        Err(e) => return Err(InitError::boot(InitStep::Identify, e, dev)),
+    };
+    if !info.has_valid_app {
+        let error = ErrorAwake::NoValidApp.into();
+        return Err(InitError::boot(InitStep::ValidApp, error, dev));
+    }
+    let mut dev = match dev.start_application() {
+        Ok(dev) => dev,
+        Err(ModeChangeError { dev, error }) => {
+            return Err(InitError::boot(InitStep::StartApplication, error, dev))
+        }
+    };
+    delay.delay_ms(1);
+    let config = Ccs811Config {
+        baseline: None,
+        ..*config
+    };
+    if let Err(e) = dev.apply(&config) {
+        return Err(InitError::app(InitStep::Configure, e, dev));
+    }
+    Ok(dev)
+}
@@ -0,0 +1,341 @@
+use crate::filter::round;
+use crate::{AlgorithmResult, CalibratedResult};
+
+/// Maximum number of knots of an `Eco2Calibration`.
+pub const MAX_CALIBRATION_KNOTS: usize = 4;
+
+/// Size of the persisted form of an `Eco2Calibration`.
+///
+/// See `Eco2Calibration::to_bytes()`.
+pub const CALIBRATION_BYTES: usize = 1 + 4 * MAX_CALIBRATION_KNOTS;
+
+/// Piecewise-linear eCO2 correction.
+///
+/// The correction is defined by 2 to `MAX_CALIBRATION_KNOTS` knots mapping
+/// an eCO2 value from the device to a corrected value. Values between knots
+/// are interpolated and values outside are extrapolated from the first or
+/// last segment. A linear correction has two knots.
+///
+/// The corrected values are saturated to [0..65535] ppm.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Eco2Calibration {
+    knots: [(u16, u16); MAX_CALIBRATION_KNOTS],
+    len: usize,
+}
+
+impl Eco2Calibration {
+    /// Create a new correction from the knots: (eCO2, corrected eCO2).
+    ///
+    /// Returns `None` if there are not 2 to `MAX_CALIBRATION_KNOTS` knots or
+    /// the eCO2 values are not strictly increasing.
+    pub fn new(knots: &[(u16, u16)]) -> Option<Self> {
+        if knots.len() < 2
+            || knots.len() > MAX_CALIBRATION_KNOTS
+            || knots.windows(2).any(|w| w[0].0 >= w[1].0)
+        {
+            return None;
+        }
+        let mut calibration = Eco2Calibration {
+            knots: [(0, 0); MAX_CALIBRATION_KNOTS],
+            len: knots.len(),
+        };
+        calibration.knots[..knots.len()].copy_from_slice(knots);
+        Some(calibration)
+    }
+
+    /// Get the knots: (eCO2, corrected eCO2).
+    pub fn knots(&self) -> &[(u16, u16)] {
+        &self.knots[..self.len]
+    }
+
+    /// Get the corrected value of an eCO2 reading (ppm).
+    pub fn correct(&self, eco2: u16) -> u16 {
+        let knots = self.knots();
+        let segment = knots
+            .windows(2)
+            .position(|w| eco2 < w[1].0)
+            .unwrap_or(knots.len() - 2);
+        let (x0, y0) = knots[segment];
+        let (x1, y1) = knots[segment + 1];
+        let (x0, y0, x1, y1) = (i64::from(x0), i64::from(y0), i64::from(x1), i64::from(y1));
+        let corrected = y0 + (i64::from(eco2) - x0) * (y1 - y0) / (x1 - x0);
+        corrected.clamp(0, i64::from(u16::MAX)) as u16
+    }
+
+    /// Apply the correction to a result.
+    ///
+    /// The result itself is kept unchanged.
+    pub fn apply(&self, result: &AlgorithmResult) -> CalibratedResult {
+        CalibratedResult {
+            result: *result,
+            corrected_eco2: self.correct(result.eco2),
+        }
+    }
+
+    /// Get the correction in a form suitable for persistent storage.
+    pub fn to_bytes(&self) -> [u8; CALIBRATION_BYTES] {
+        let mut bytes = [0; CALIBRATION_BYTES];
+        bytes[0] = self.len as u8;
+        for (i, (eco2, corrected)) in self.knots().iter().enumerate() {
+            bytes[1 + 4 * i..3 + 4 * i].copy_from_slice(&eco2.to_be_bytes());
+            bytes[3 + 4 * i..5 + 4 * i].copy_from_slice(&corrected.to_be_bytes());
+        }
+        bytes
+    }
+
+    /// Restore a correction from its persisted form.
+    ///
+    /// Returns `None` if the data is not a valid correction.
+    pub fn from_bytes(bytes: &[u8; CALIBRATION_BYTES]) -> Option<Self> {
+        let len = usize::from(bytes[0]);
+        if len > MAX_CALIBRATION_KNOTS {
+            return None;
+        }
+        let mut knots = [(0, 0); MAX_CALIBRATION_KNOTS];
+        for (i, knot) in knots.iter_mut().enumerate().take(len) {
+            *knot = (
+                u16::from_be_bytes([bytes[1 + 4 * i], bytes[2 + 4 * i]]),
+                u16::from_be_bytes([bytes[3 + 4 * i], bytes[4 + 4 * i]]),
+            );
+        }
+        Self::new(&knots[..len])
+    }
+}
+
+/// Collects paired eCO2 and reference CO2 samples and fits an
+/// `Eco2Calibration`.
+///
+/// The reference values typically come from an NDIR CO2 sensor placed next
+/// to the CCS811. Up to `N` samples are kept. When full, the oldest sample
+/// is replaced.
+#[derive(Debug, Clone)]
+pub struct Eco2Calibrator<const N: usize> {
+    samples: [(u16, u16); N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> Default for Eco2Calibrator<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Eco2Calibrator<N> {
+    /// Create a new calibrator without samples.
+    pub fn new() -> Self {
+        Eco2Calibrator {
+            samples: [(0, 0); N],
+            len: 0,
+            next: 0,
+        }
+    }
+
+    /// Get the number of samples.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Whether there are no samples.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Remove all samples.
+    pub fn clear(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+
+    /// Add a sample pairing a result with the reference CO2 value (ppm)
+    /// measured at the same time.
+    pub fn add(&mut self, result: &AlgorithmResult, reference_ppm: u16) {
+        self.add_sample(result.eco2, reference_ppm);
+    }
+
+    /// Add a sample pairing an eCO2 value with the reference CO2 value (ppm)
+    /// measured at the same time.
+    pub fn add_sample(&mut self, eco2: u16, reference_ppm: u16) {
+        if N == 0 {
+            return;
+        }
+        self.samples[self.next] = (eco2, reference_ppm);
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    /// Fit a linear correction with the least squares method.
+    ///
+    /// The knots are placed at the lowest and highest eCO2 sampled.
+    /// Returns `None` if there are not at least two different eCO2 values.
+    pub fn fit_linear(&self) -> Option<Eco2Calibration> {
+        let samples = &self.samples[..self.len];
+        let (min, max) = samples.iter().fold((u16::MAX, 0), |(min, max), (x, _)| {
+            (min.min(*x), max.max(*x))
+        });
+        if samples.is_empty() || min == max {
+            return None;
+        }
+        let count = samples.len() as f32;
+        let mean_x = samples.iter().map(|(x, _)| f32::from(*x)).sum::<f32>() / count;
+        let mean_y = samples.iter().map(|(_, y)| f32::from(*y)).sum::<f32>() / count;
+        let (covariance, variance) = samples.iter().fold((0.0, 0.0), |(cov, var), (x, y)| {
+            let dx = f32::from(*x) - mean_x;
+            (cov + dx * (f32::from(*y) - mean_y), var + dx * dx)
+        });
+        let gain = covariance / variance;
+        let at = |x: u16| round(mean_y + gain * (f32::from(x) - mean_x));
+        Eco2Calibration::new(&[(min, at(min)), (max, at(max))])
+    }
+
+    /// Fit a piecewise-linear correction with the given number of knots.
+    ///
+    /// The samples are sorted by eCO2 and split into `knots` groups of the
+    /// same size. Each knot is placed at the mean eCO2 and reference value of
+    /// its group.
+    /// Returns `None` if the number of knots is not within
+    /// 2..=`MAX_CALIBRATION_KNOTS`, there are fewer samples than knots or
+    /// the groups do not have different eCO2 means.
+    pub fn fit_piecewise(&self, knots: usize) -> Option<Eco2Calibration> {
+        if !(2..=MAX_CALIBRATION_KNOTS).contains(&knots) || self.len < knots {
+            return None;
+        }
+        let mut samples = self.samples;
+        let samples = &mut samples[..self.len];
+        samples.sort_unstable();
+        let mut result = [(0, 0); MAX_CALIBRATION_KNOTS];
+        for (i, knot) in result.iter_mut().enumerate().take(knots) {
+            let group = &samples[i * samples.len() / knots..(i + 1) * samples.len() / knots];
+            let count = group.len() as u32;
+            let sum = group.iter().fold((0, 0), |(sx, sy), (x, y)| {
+                (sx + u32::from(*x), sy + u32::from(*y))
+            });
+            *knot = ((sum.0 / count) as u16, (sum.1 / count) as u16);
+        }
+        Eco2Calibration::new(&result[..knots])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_invalid_knots() {
+        assert_eq!(None, Eco2Calibration::new(&[(400, 400)]));
+        assert_eq!(None, Eco2Calibration::new(&[(400, 400), (400, 500)]));
+        assert_eq!(
+            None,
+            Eco2Calibration::new(&[(1, 1), (2, 2), (3, 3), (4, 4), (5, 5)])
+        );
+    }
+
+    #[test]
+    fn interpolates_and_extrapolates() {
+        let calibration = Eco2Calibration::new(&[(400, 420), (1000, 900), (2000, 1500)]).unwrap();
+        assert_eq!(420, calibration.correct(400));
+        assert_eq!(660, calibration.correct(700));
+        assert_eq!(1200, calibration.correct(1500));
+        assert_eq!(1800, calibration.correct(2500));
+        assert_eq!(340, calibration.correct(300));
+        assert_eq!(100, calibration.correct(0));
+    }
+
+    #[test]
+    fn applies_to_result() {
+        let calibration = Eco2Calibration::new(&[(400, 400), (1000, 800)]).unwrap();
+        let result = AlgorithmResult {
+            eco2: 700,
+            ..Default::default()
+        };
+        assert_eq!(
+            CalibratedResult {
+                result,
+                corrected_eco2: 600,
+            },
+            calibration.apply(&result)
+        );
+    }
+
+    #[test]
+    fn can_persist() {
+        let calibration = Eco2Calibration::new(&[(400, 420), (1000, 900), (2000, 1500)]).unwrap();
+        let bytes = calibration.to_bytes();
+        assert_eq!(
+            [
+                3, 0x01, 0x90, 0x01, 0xA4, 0x03, 0xE8, 0x03, 0x84, 0x07, 0xD0, 0x05, 0xDC, 0, 0, 0,
+                0
+            ],
+            bytes
+        );
+        assert_eq!(Some(calibration), Eco2Calibration::from_bytes(&bytes));
+    }
+
+    #[test]
+    fn rejects_invalid_persisted_data() {
+        assert_eq!(None, Eco2Calibration::from_bytes(&[0; CALIBRATION_BYTES]));
+        let mut bytes = [0; CALIBRATION_BYTES];
+        bytes[0] = 5;
+        assert_eq!(None, Eco2Calibration::from_bytes(&bytes));
+    }
+
+    #[test]
+    fn fits_linear() {
+        let mut calibrator = Eco2Calibrator::<8>::new();
+        assert_eq!(None, calibrator.fit_linear());
+        for eco2 in [400, 600, 800, 1000] {
+            calibrator.add_sample(eco2, eco2 / 2 + 300);
+        }
+        let calibration = calibrator.fit_linear().unwrap();
+        assert_eq!(&[(400, 500), (1000, 800)], calibration.knots());
+    }
+
+    #[test]
+    fn linear_fit_needs_different_values() {
+        let mut calibrator = Eco2Calibrator::<8>::new();
+        calibrator.add_sample(400, 500);
+        calibrator.add_sample(400, 600);
+        assert_eq!(None, calibrator.fit_linear());
+    }
+
+    #[test]
+    fn fits_piecewise() {
+        let mut calibrator = Eco2Calibrator::<8>::new();
+        for (eco2, reference) in [
+            (1000, 900),
+            (400, 400),
+            (2000, 1400),
+            (500, 500),
+            (1100, 1000),
+            (2200, 1600),
+        ] {
+            calibrator.add_sample(eco2, reference);
+        }
+        assert_eq!(None, calibrator.fit_piecewise(1));
+        assert_eq!(None, calibrator.fit_piecewise(MAX_CALIBRATION_KNOTS + 1));
+        let calibration = calibrator.fit_piecewise(3).unwrap();
+        assert_eq!(
+            &[(450, 450), (1050, 950), (2100, 1500)],
+            calibration.knots()
+        );
+    }
+
+    #[test]
+    fn replaces_oldest_sample_when_full() {
+        let mut calibrator = Eco2Calibrator::<2>::new();
+        calibrator.add(
+            &AlgorithmResult {
+                eco2: 5000,
+                ..Default::default()
+            },
+            400,
+        );
+        calibrator.add_sample(400, 400);
+        calibrator.add_sample(800, 800);
+        assert_eq!(2, calibrator.len());
+        let calibration = calibrator.fit_linear().unwrap();
+        assert_eq!(&[(400, 400), (800, 800)], calibration.knots());
+        calibrator.clear();
+        assert!(calibrator.is_empty());
+    }
+}
@@ -0,0 +1,59 @@
+/// Monotonic clock in milliseconds.
+///
+/// The value may wrap around. This is implemented for closures returning
+/// the current time.
+pub trait Clock {
+    /// Get the current time in milliseconds.
+    fn now_ms(&self) -> u32;
+}
+
+impl<F: Fn() -> u32> Clock for F {
+    fn now_ms(&self) -> u32 {
+        self()
+    }
+}
+
+/// Clock based on `std::time::Instant`.
+///
+/// The time is counted from the clock creation.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct StdClock {
+    start: std::time::Instant,
+}
+
+#[cfg(feature = "std")]
+impl StdClock {
+    /// Create a new clock starting at 0.
+    pub fn new() -> Self {
+        StdClock {
+            start: std::time::Instant::now(),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl Default for StdClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(feature = "std")]
+impl Clock for StdClock {
+    fn now_ms(&self) -> u32 {
+        self.start.elapsed().as_millis() as u32
+    }
+}
+
+/// Clock based on `embassy_time::Instant`.
+#[cfg(feature = "embassy-time")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EmbassyClock;
+
+#[cfg(feature = "embassy-time")]
+impl Clock for EmbassyClock {
+    fn now_ms(&self) -> u32 {
+        embassy_time::Instant::now().as_millis() as u32
+    }
+}
@@ -1,7 +1,8 @@
 use crate::hal::{delay::DelayNs, digital::OutputPin};
+use crate::register_access::{check_app_mode, HARDWARE_ID, HARDWARE_VERSION_MAJOR};
 use crate::{
-    hal, mode, ActionInProgress, BitFlags, Ccs811, Ccs811Awake, Ccs811Device, Error, ErrorAwake,
-    FirmwareMode, ModeChangeError, Register, SlaveAddr,
+    hal, mode, private, ActionInProgress, BitFlags, Ccs811, Ccs811Awake, Ccs811Device, DeviceInfo,
+    Error, ErrorAwake, FirmwareMode, ModeChangeError, NoPin, Register, SlaveAddr, StatusCheck,
 };
 use core::marker::PhantomData;
 
@@ -10,24 +11,78 @@ impl<I2C, NWAKE, WAKEDELAY> Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot> {
     ///
     /// See `Ccs811Awake` for the case where the nWAKE pin is not used.
     pub fn new(i2c: I2C, address: SlaveAddr, n_wake_pin: NWAKE, wake_delay: WAKEDELAY) -> Self {
-        Self::create(i2c, address.addr(), n_wake_pin, wake_delay)
+        Self::create(i2c, address.addr(), n_wake_pin, wake_delay, NoPin)
     }
 }
 
-impl<I2C, NWAKE, WAKEDELAY, MODE> Ccs811<I2C, NWAKE, WAKEDELAY, MODE> {
-    pub(crate) fn create(i2c: I2C, address: u8, n_wake_pin: NWAKE, wake_delay: WAKEDELAY) -> Self {
-        Self::from_awake_dev(Ccs811Awake::create(i2c, address), n_wake_pin, wake_delay)
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY> Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+{
+    /// Create new instance of the CCS811 device and identify it.
+    ///
+    /// Returns the device together with its information.
+    /// See `Ccs811Device::identify()`.
+    #[allow(clippy::type_complexity)]
+    pub fn new_identified(
+        i2c: I2C,
+        address: SlaveAddr,
+        n_wake_pin: NWAKE,
+        wake_delay: WAKEDELAY,
+    ) -> Result<(Self, DeviceInfo), ModeChangeError<Error<CommE, PinE>, Self>> {
+        let mut dev = Self::new(i2c, address, n_wake_pin, wake_delay);
+        match dev.identify() {
+            Ok(info) => Ok((dev, info)),
+            Err(e) => Err(ModeChangeError::new(dev, e)),
+        }
+    }
+}
+
+impl<I2C, NWAKE, WAKEDELAY, NRESET> Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot, NRESET> {
+    /// Create new instance of the CCS811 device with the nRESET pin connected.
+    ///
+    /// This enables resetting the device through the nRESET pin.
+    /// See `hardware_reset()`.
+    pub fn new_with_reset_pin(
+        i2c: I2C,
+        address: SlaveAddr,
+        n_wake_pin: NWAKE,
+        n_reset_pin: NRESET,
+        wake_delay: WAKEDELAY,
+    ) -> Self {
+        Self::create(i2c, address.addr(), n_wake_pin, wake_delay, n_reset_pin)
+    }
+}
+
+impl<I2C, NWAKE, WAKEDELAY, MODE, NRESET> Ccs811<I2C, NWAKE, WAKEDELAY, MODE, NRESET> {
+    pub(crate) fn create(
+        i2c: I2C,
+        address: u8,
+        n_wake_pin: NWAKE,
+        wake_delay: WAKEDELAY,
+        n_reset_pin: NRESET,
+    ) -> Self {
+        Self::from_awake_dev(
+            Ccs811Awake::create(i2c, address),
+            n_wake_pin,
+            wake_delay,
+            n_reset_pin,
+        )
     }
 
     pub(crate) fn from_awake_dev(
         dev: Ccs811Awake<I2C, MODE>,
         n_wake_pin: NWAKE,
         wake_delay: WAKEDELAY,
+        n_reset_pin: NRESET,
     ) -> Self {
         Ccs811 {
             dev,
             n_wake_pin,
             wake_delay,
+            n_reset_pin,
             _mode: PhantomData,
         }
     }
@@ -40,16 +95,64 @@ impl<I2C> Ccs811Awake<I2C, mode::Boot> {
     }
 }
 
+impl<I2C, E> Ccs811Awake<I2C, mode::Boot>
+where
+    I2C: hal::i2c::I2c<Error = E>,
+{
+    /// Create new instance of an already awake CCS811 device and identify it.
+    ///
+    /// Returns the device together with its information.
+    /// See `Ccs811Device::identify()`.
+    #[allow(clippy::type_complexity)]
+    pub fn new_identified(
+        i2c: I2C,
+        address: SlaveAddr,
+    ) -> Result<(Self, DeviceInfo), ModeChangeError<ErrorAwake<E>, Self>> {
+        let mut dev = Self::new(i2c, address);
+        match dev.identify() {
+            Ok(info) => Ok((dev, info)),
+            Err(e) => Err(ModeChangeError::new(dev, e)),
+        }
+    }
+}
+
 impl<I2C, MODE> Ccs811Awake<I2C, MODE> {
     pub(crate) fn create(i2c: I2C, address: u8) -> Self {
         Ccs811Awake {
             i2c,
             address,
-            meas_mode_reg: 0,
+            meas_mode_reg: None,
             in_progress: ActionInProgress::None,
+            status_check: StatusCheck::default(),
+            config: None,
+            last_sample_ms: None,
             _mode: PhantomData,
         }
     }
+
+    /// Convert into another mode keeping the driver settings and the
+    /// configuration record.
+    ///
+    /// The measurement mode register cache and any action in progress
+    /// are reset since the device has been restarted.
+    pub(crate) fn into_mode<TMODE>(self) -> Ccs811Awake<I2C, TMODE> {
+        let mut dev = Ccs811Awake::create(self.i2c, self.address);
+        dev.status_check = self.status_check;
+        dev.config = self.config;
+        dev
+    }
+
+    /// Get the status check policy.
+    pub fn status_check(&self) -> StatusCheck {
+        self.status_check
+    }
+
+    /// Set the status check policy.
+    ///
+    /// See `StatusCheck` for the available policies.
+    pub fn set_status_check(&mut self, policy: StatusCheck) {
+        self.status_check = policy;
+    }
 }
 
 impl<I2C, E, MODE> Ccs811Awake<I2C, MODE>
@@ -60,6 +163,36 @@ where
     pub fn destroy(self) -> I2C {
         self.i2c
     }
+
+    /// Run several operations checking the STATUS register only once at the end.
+    ///
+    /// The status checks after each register access are skipped while `f`
+    /// runs. Afterwards STATUS (and ERROR_ID, if an error is flagged) is read
+    /// once regardless of the configured `StatusCheck` policy. In application
+    /// mode `UnexpectedReset` is returned if the device returned to boot mode.
+    pub fn batch<T, F>(&mut self, f: F) -> Result<T, ErrorAwake<E>>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ErrorAwake<E>>,
+        MODE: private::ModeMarker,
+    {
+        let value = self.without_status_check(f)?;
+        let status = self.read_status_with_errors()?;
+        if MODE::IS_APP {
+            check_app_mode(status)?;
+        }
+        Ok(value)
+    }
+
+    pub(crate) fn without_status_check<T, F>(&mut self, f: F) -> Result<T, ErrorAwake<E>>
+    where
+        F: FnOnce(&mut Self) -> Result<T, ErrorAwake<E>>,
+    {
+        let policy = self.status_check;
+        self.status_check = StatusCheck::Deferred;
+        let result = f(self);
+        self.status_check = policy;
+        result
+    }
 }
 impl<I2C, E, MODE> Ccs811Awake<I2C, MODE>
 where
@@ -83,6 +216,92 @@ where
     pub fn destroy(self) -> (I2C, NWAKE, WAKEDELAY) {
         (self.dev.destroy(), self.n_wake_pin, self.wake_delay)
     }
+}
+
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, MODE, NRESET> Ccs811<I2C, NWAKE, WAKEDELAY, MODE, NRESET>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+    NRESET: OutputPin<Error = PinE>,
+{
+    /// Destroy driver instance, return I²C bus, nWAKE pin, nRESET pin
+    /// and wake delay instances.
+    pub fn destroy_with_reset_pin(self) -> (I2C, NWAKE, NRESET, WAKEDELAY) {
+        (
+            self.dev.destroy(),
+            self.n_wake_pin,
+            self.n_reset_pin,
+            self.wake_delay,
+        )
+    }
+
+    /// Reset the device through the nRESET pin.
+    ///
+    /// nRESET is held low for 20us and then 2ms are waited for the device
+    /// to restart in boot mode.
+    /// This works even if the device does not respond on the I²C bus anymore,
+    /// so it can be used as a fallback if a mode change fails.
+    #[allow(clippy::type_complexity)]
+    pub fn hardware_reset(
+        mut self,
+    ) -> Result<
+        Ccs811<I2C, NWAKE, WAKEDELAY, mode::Boot, NRESET>,
+        ModeChangeError<Error<CommE, PinE>, Self>,
+    > {
+        if let Err(e) = self.n_reset_pin.set_low() {
+            return Err(ModeChangeError::new(self, Error::Pin(e)));
+        }
+        self.wake_delay.delay_us(20);
+        if let Err(e) = self.n_reset_pin.set_high() {
+            return Err(ModeChangeError::new(self, Error::Pin(e)));
+        }
+        self.wake_delay.delay_ms(2);
+        let Ccs811 {
+            dev,
+            n_wake_pin,
+            wake_delay,
+            n_reset_pin,
+            ..
+        } = self;
+        Ok(Ccs811::from_awake_dev(
+            dev.into_mode(),
+            n_wake_pin,
+            wake_delay,
+            n_reset_pin,
+        ))
+    }
+}
+
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, MODE, NRESET> Ccs811<I2C, NWAKE, WAKEDELAY, MODE, NRESET>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+{
+    /// Get the status check policy.
+    pub fn status_check(&self) -> StatusCheck {
+        self.dev.status_check()
+    }
+
+    /// Set the status check policy.
+    ///
+    /// See `StatusCheck` for the available policies.
+    pub fn set_status_check(&mut self, policy: StatusCheck) {
+        self.dev.set_status_check(policy);
+    }
+
+    /// Wake the device once and run several operations on it.
+    ///
+    /// nWAKE is asserted before calling `f`, which gets the awake device
+    /// to perform any number of operations, and released afterwards, also
+    /// if `f` returns an error.
+    pub fn with_awake<T, F>(&mut self, f: F) -> Result<T, Error<CommE, PinE>>
+    where
+        F: FnOnce(&mut Ccs811Awake<I2C, MODE>) -> Result<T, ErrorAwake<CommE>>,
+    {
+        self.on_awaken(|s| f(&mut s.dev))
+    }
 
     pub(crate) fn on_awaken<T, F>(&mut self, f: F) -> Result<T, Error<CommE, PinE>>
     where
@@ -128,7 +347,10 @@ where
     pub(crate) fn wrap_mode_change<TMODE, F>(
         mut self,
         f: F,
-    ) -> Result<Ccs811<I2C, NWAKE, WAKEDELAY, TMODE>, ModeChangeError<Error<CommE, PinE>, Self>>
+    ) -> Result<
+        Ccs811<I2C, NWAKE, WAKEDELAY, TMODE, NRESET>,
+        ModeChangeError<Error<CommE, PinE>, Self>,
+    >
     where
         F: FnOnce(
             Ccs811Awake<I2C, MODE>,
@@ -145,26 +367,37 @@ where
             dev,
             mut n_wake_pin,
             mut wake_delay,
+            n_reset_pin,
             ..
         } = self;
         let result = f(dev);
         if let Err(e) = n_wake_pin.set_high() {
             return match result {
-                Ok(Ccs811Awake { i2c, address, .. }) => Err(ModeChangeError {
-                    dev: Ccs811::create(i2c, address, n_wake_pin, wake_delay),
+                Ok(dev) => Err(ModeChangeError {
+                    dev: Ccs811::from_awake_dev(
+                        dev.into_mode(),
+                        n_wake_pin,
+                        wake_delay,
+                        n_reset_pin,
+                    ),
                     error: Error::Pin(e),
                 }),
                 Err(ModeChangeError { dev, error }) => Err(ModeChangeError {
-                    dev: Ccs811::from_awake_dev(dev, n_wake_pin, wake_delay),
+                    dev: Ccs811::from_awake_dev(dev, n_wake_pin, wake_delay, n_reset_pin),
                     error: error.into(),
                 }),
             };
         }
         wake_delay.delay_us(20);
         match result {
-            Ok(dev) => Ok(Ccs811::from_awake_dev(dev, n_wake_pin, wake_delay)),
+            Ok(dev) => Ok(Ccs811::from_awake_dev(
+                dev,
+                n_wake_pin,
+                wake_delay,
+                n_reset_pin,
+            )),
             Err(ModeChangeError { dev, error }) => Err(ModeChangeError {
-                dev: Ccs811::from_awake_dev(dev, n_wake_pin, wake_delay),
+                dev: Ccs811::from_awake_dev(dev, n_wake_pin, wake_delay, n_reset_pin),
                 error: error.into(),
             }),
         }
@@ -179,12 +412,7 @@ where
 
     fn firmware_mode(&mut self) -> Result<FirmwareMode, Self::Error> {
         let status = self.read_status()?;
-        let mode = if (status & BitFlags::FW_MODE) != 0 {
-            FirmwareMode::Application
-        } else {
-            FirmwareMode::Boot
-        };
-        Ok(mode)
+        Ok(get_firmware_mode(status))
     }
 
     fn has_valid_app(&mut self) -> Result<bool, Self::Error> {
@@ -210,9 +438,37 @@ where
         let version = self.read_register_2bytes(Register::FW_APP_VERSION)?;
         Ok(((version[0] & 0xF0) >> 4, version[0] & 0xF, version[1]))
     }
+
+    fn identify(&mut self) -> Result<DeviceInfo, Self::Error> {
+        // The STATUS register is only meaningful once the identity is confirmed.
+        let (hardware_id, hardware_version) =
+            self.without_status_check(|s| Ok((s.hardware_id()?, s.hardware_version()?)))?;
+        if hardware_id != HARDWARE_ID || hardware_version.0 != HARDWARE_VERSION_MAJOR {
+            return Err(ErrorAwake::UnexpectedIdentity(
+                hardware_id,
+                hardware_version,
+            ));
+        }
+        let (bootloader_version, application_version) = self.without_status_check(|s| {
+            Ok((
+                s.firmware_bootloader_version()?,
+                s.firmware_application_version()?,
+            ))
+        })?;
+        let status = self.read_status()?;
+        Ok(DeviceInfo {
+            hardware_id,
+            hardware_version,
+            bootloader_version,
+            application_version,
+            has_valid_app: (status & BitFlags::APP_VALID) != 0,
+            firmware_mode: get_firmware_mode(status),
+        })
+    }
 }
 
-impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, MODE> Ccs811Device for Ccs811<I2C, NWAKE, WAKEDELAY, MODE>
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, MODE, NRESET> Ccs811Device
+    for Ccs811<I2C, NWAKE, WAKEDELAY, MODE, NRESET>
 where
     I2C: hal::i2c::I2c<Error = CommE>,
     NWAKE: OutputPin<Error = PinE>,
@@ -243,4 +499,16 @@ where
     fn firmware_application_version(&mut self) -> Result<(u8, u8, u8), Self::Error> {
         self.on_awaken(|s| s.dev.firmware_application_version())
     }
+
+    fn identify(&mut self) -> Result<DeviceInfo, Self::Error> {
+        self.on_awaken(|s| s.dev.identify())
+    }
+}
+
+pub(crate) fn get_firmware_mode(status: u8) -> FirmwareMode {
+    if (status & BitFlags::FW_MODE) != 0 {
+        FirmwareMode::Application
+    } else {
+        FirmwareMode::Boot
+    }
 }
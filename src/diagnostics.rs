@@ -0,0 +1,157 @@
+use crate::hal::{delay::DelayNs, digital::OutputPin};
+use crate::register_access::{get_errors, BitFlags, Register, HARDWARE_ID, HARDWARE_VERSION_MAJOR};
+use crate::{hal, mode, Ccs811, Ccs811AppMode, Ccs811Awake, DeviceErrors, Error, ErrorAwake};
+
+/// Interval between checks for new data during the self-test measurement cycle
+const DATA_POLL_INTERVAL_MS: u32 = 100;
+/// Maximum number of checks for new data during the self-test measurement cycle
+const DATA_POLL_COUNT: u32 = 20;
+
+/// Self-test result with the pass/fail state of each check.
+///
+/// See `Ccs811Awake::self_test()`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct DiagnosticReport {
+    /// The hardware ID and hardware major version are the expected ones.
+    pub identity: bool,
+    /// A valid application firmware is loaded.
+    pub valid_app: bool,
+    /// New data was available within the measurement cycle.
+    pub measurement: bool,
+    /// Raw current and voltage are within plausible ranges.
+    pub raw_data: bool,
+    /// Neither a heater fault nor a heater supply error was reported.
+    pub heater: bool,
+    /// No maximum sensor resistance error was reported.
+    pub resistance: bool,
+    /// The baseline register reads back after writing it.
+    pub baseline: bool,
+    /// Raw current through the sensor (μA) read during the measurement cycle.
+    pub raw_current: u8,
+    /// Raw voltage across the sensor read during the measurement cycle.
+    ///
+    /// 1023 = 1.65V
+    pub raw_voltage: u16,
+    /// Errors reported by the device in ERROR_ID.
+    pub errors: DeviceErrors,
+}
+
+impl DiagnosticReport {
+    /// Whether all checks passed.
+    pub fn passed(&self) -> bool {
+        self.identity
+            && self.valid_app
+            && self.measurement
+            && self.raw_data
+            && self.heater
+            && self.resistance
+            && self.baseline
+    }
+}
+
+impl<I2C, E> Ccs811Awake<I2C, mode::App>
+where
+    I2C: hal::i2c::I2c<Error = E>,
+{
+    /// Run a self-test of the device.
+    ///
+    /// This checks the identity and the application validity, runs a
+    /// measurement cycle of up to 2 seconds in `ConstantPower1s` mode, checks
+    /// the raw data plausibility and the heater and sensor resistance errors
+    /// in ERROR_ID and writes the current baseline back, checking that it
+    /// reads back the same.
+    ///
+    /// The device must be in `Idle` or `ConstantPower1s` mode. Otherwise
+    /// `Error::InvalidInputData` is returned and nothing is done. Switching
+    /// back from `ConstantPower1s` to a mode with a lower sample rate would
+    /// require placing the device in `Idle` mode for at least 10 minutes
+    /// first, which the self-test does not do. Place the device in `Idle`
+    /// mode before running the self-test from such a mode.
+    ///
+    /// The measurement mode is restored afterwards, also if the measurement
+    /// cycle fails. The recorded configuration is left unchanged.
+    /// Device errors are reported in the result instead of as an `Err`.
+    pub fn self_test<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<DiagnosticReport, ErrorAwake<E>> {
+        self.without_status_check(|dev| dev.run_self_test(delay))
+    }
+
+    fn run_self_test<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<DiagnosticReport, ErrorAwake<E>> {
+        let meas_mode_reg = self.meas_mode()?;
+        let mode = (meas_mode_reg >> 4) & 0b111;
+        if mode > 1 {
+            return Err(ErrorAwake::InvalidInputData);
+        }
+        let mut report = DiagnosticReport::default();
+        let id = self.read_register_1byte(Register::HW_ID)?;
+        let version = self.read_register_1byte(Register::HW_VERSION)?;
+        report.identity = id == HARDWARE_ID && (version >> 4) == HARDWARE_VERSION_MAJOR;
+        let status = self.read_status_only()?;
+        report.valid_app = (status & BitFlags::APP_VALID) != 0;
+
+        let measuring = (meas_mode_reg & !(0b111 << 4)) | (1 << 4);
+        let measured = self.run_measurement_cycle(measuring, &mut report, delay);
+        let restored = self.write_register_1byte(Register::MEAS_MODE, meas_mode_reg);
+        measured?;
+        restored?;
+
+        let error_id = self.read_register_1byte(Register::ERROR_ID)?;
+        if let Err(errors) = get_errors(error_id) {
+            report.errors = errors;
+        }
+        report.heater = !report.errors.heater_fault && !report.errors.heater_supply;
+        report.resistance = !report.errors.max_resistance;
+
+        let baseline = self.baseline()?;
+        self.write_baseline(baseline)?;
+        report.baseline = self.baseline()? == baseline;
+        Ok(report)
+    }
+
+    fn run_measurement_cycle<D: DelayNs>(
+        &mut self,
+        meas_mode: u8,
+        report: &mut DiagnosticReport,
+        delay: &mut D,
+    ) -> Result<(), ErrorAwake<E>> {
+        self.write_register_1byte(Register::MEAS_MODE, meas_mode)?;
+        for _ in 0..DATA_POLL_COUNT {
+            delay.delay_ms(DATA_POLL_INTERVAL_MS);
+            let status = self.read_status_only()?;
+            if (status & BitFlags::DATA_READY) != 0 {
+                report.measurement = true;
+                break;
+            }
+        }
+        if report.measurement {
+            let (current, voltage) = self.raw_data()?;
+            report.raw_current = current;
+            report.raw_voltage = voltage;
+            report.raw_data = current > 0 && voltage > 0 && voltage < 1023;
+        }
+        Ok(())
+    }
+}
+
+impl<I2C, CommE, PinE, NWAKE, WAKEDELAY, NRESET> Ccs811<I2C, NWAKE, WAKEDELAY, mode::App, NRESET>
+where
+    I2C: hal::i2c::I2c<Error = CommE>,
+    NWAKE: OutputPin<Error = PinE>,
+    WAKEDELAY: DelayNs,
+{
+    /// Run a self-test of the device.
+    ///
+    /// The device is kept awake during the complete self-test.
+    /// See `Ccs811Awake::self_test()`.
+    pub fn self_test<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+    ) -> Result<DiagnosticReport, Error<CommE, PinE>> {
+        self.with_awake(|dev| dev.self_test(delay))
+    }
+}
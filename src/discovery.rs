@@ -0,0 +1,81 @@
+use crate::common_impl::get_firmware_mode;
+use crate::hal::i2c::{Error as _, ErrorKind, I2c};
+use crate::register_access::HARDWARE_ID;
+use crate::{mode, Ccs811Awake, FirmwareMode, Register, SlaveAddr};
+
+/// CCS811 device found on the I²C bus. See `scan()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DiscoveredSensor {
+    /// Slave address the device responded to.
+    pub address: SlaveAddr,
+    /// Firmware mode the device is in.
+    pub firmware_mode: FirmwareMode,
+    meas_mode_reg: u8,
+}
+
+/// Driver for a discovered device in its current firmware mode.
+#[derive(Debug)]
+pub enum DiscoveredDevice<I2C> {
+    /// Device in boot mode.
+    Boot(Ccs811Awake<I2C, mode::Boot>),
+    /// Device in application mode.
+    App(Ccs811Awake<I2C, mode::App>),
+}
+
+impl DiscoveredSensor {
+    /// Create an already awake driver for this device on the given I²C bus.
+    ///
+    /// For shared-bus setups, the bus can be an `embedded-hal-bus` device or
+    /// a mutable reference to the bus.
+    pub fn into_device<I2C>(self, i2c: I2C) -> DiscoveredDevice<I2C> {
+        match self.firmware_mode {
+            FirmwareMode::Boot => {
+                DiscoveredDevice::Boot(Ccs811Awake::create(i2c, self.address.addr()))
+            }
+            FirmwareMode::Application => {
+                let mut dev = Ccs811Awake::create(i2c, self.address.addr());
+                dev.meas_mode_reg = Some(self.meas_mode_reg);
+                DiscoveredDevice::App(dev)
+            }
+        }
+    }
+}
+
+/// Probe both CCS811 slave addresses (0x5A and 0x5B) on an I²C bus.
+///
+/// A device is reported if it acknowledges its address and its hardware ID
+/// is 0x81. The result is in address order: 0x5A, 0x5B.
+/// The devices must be awake (nWAKE low) for the scan.
+pub fn scan<I2C: I2c>(i2c: &mut I2C) -> Result<[Option<DiscoveredSensor>; 2], I2C::Error> {
+    Ok([
+        probe(i2c, SlaveAddr::Alternative(false))?,
+        probe(i2c, SlaveAddr::Alternative(true))?,
+    ])
+}
+
+fn probe<I2C: I2c>(
+    i2c: &mut I2C,
+    address: SlaveAddr,
+) -> Result<Option<DiscoveredSensor>, I2C::Error> {
+    let mut data = [0];
+    match i2c.write_read(address.addr(), &[Register::HW_ID], &mut data) {
+        Err(e) if matches!(e.kind(), ErrorKind::NoAcknowledge(_)) => return Ok(None),
+        Err(e) => return Err(e),
+        Ok(_) => (),
+    }
+    if data[0] != HARDWARE_ID {
+        return Ok(None);
+    }
+    i2c.write_read(address.addr(), &[Register::STATUS], &mut data)?;
+    let firmware_mode = get_firmware_mode(data[0]);
+    let mut meas_mode_reg = 0;
+    if firmware_mode == FirmwareMode::Application {
+        i2c.write_read(address.addr(), &[Register::MEAS_MODE], &mut data)?;
+        meas_mode_reg = data[0];
+    }
+    Ok(Some(DiscoveredSensor {
+        address,
+        firmware_mode,
+        meas_mode_reg,
+    }))
+}
@@ -0,0 +1,153 @@
+use crate::{drive, private, AlgorithmResult, Ccs811AppMode, MeasurementMode, ModeChangeError};
+use core::marker::PhantomData;
+
+/// Measurement drive mode marker with its `MeasurementMode`.
+pub trait DriveMode: private::Sealed {
+    /// Measurement mode configured on the device
+    const MODE: MeasurementMode;
+}
+
+/// Drive modes in which the algorithm results are updated (modes 1-3).
+pub trait AlgorithmDriveMode: DriveMode {}
+
+impl DriveMode for drive::Idle {
+    const MODE: MeasurementMode = MeasurementMode::Idle;
+}
+impl DriveMode for drive::ConstantPower1s {
+    const MODE: MeasurementMode = MeasurementMode::ConstantPower1s;
+}
+impl DriveMode for drive::PulseHeating10s {
+    const MODE: MeasurementMode = MeasurementMode::PulseHeating10s;
+}
+impl DriveMode for drive::LowPowerPulseHeating60s {
+    const MODE: MeasurementMode = MeasurementMode::LowPowerPulseHeating60s;
+}
+impl DriveMode for drive::ConstantPower250ms {
+    const MODE: MeasurementMode = MeasurementMode::ConstantPower250ms;
+}
+impl AlgorithmDriveMode for drive::ConstantPower1s {}
+impl AlgorithmDriveMode for drive::PulseHeating10s {}
+impl AlgorithmDriveMode for drive::LowPowerPulseHeating60s {}
+
+/// Device in application mode with the measurement drive mode as part of its type.
+///
+/// Only the data accessors available in the drive mode are provided:
+/// - `Idle`: none.
+/// - `ConstantPower1s`, `PulseHeating10s` and `LowPowerPulseHeating60s`:
+///   algorithm results.
+/// - `ConstantPower250ms`: raw data only, since the algorithm results are
+///   not updated in this mode.
+///
+/// Drive mode changes consume the device and return it in the new mode.
+#[derive(Debug)]
+pub struct Ccs811Drive<DEV, DRIVE> {
+    dev: DEV,
+    _drive: PhantomData<DRIVE>,
+}
+
+impl<DEV, E> Ccs811Drive<DEV, drive::Idle>
+where
+    DEV: Ccs811AppMode<Error = E>,
+{
+    /// Put a device in application mode in `Idle` drive mode.
+    pub fn new(mut dev: DEV) -> Result<Self, ModeChangeError<E, DEV>> {
+        match dev.set_mode(MeasurementMode::Idle) {
+            Ok(()) => Ok(Ccs811Drive {
+                dev,
+                _drive: PhantomData,
+            }),
+            Err(error) => Err(ModeChangeError::new(dev, error)),
+        }
+    }
+}
+
+impl<DEV, DRIVE> Ccs811Drive<DEV, DRIVE> {
+    /// Destroy the typed wrapper and return the device.
+    pub fn into_inner(self) -> DEV {
+        self.dev
+    }
+}
+
+impl<DEV, DRIVE, E> Ccs811Drive<DEV, DRIVE>
+where
+    DEV: Ccs811AppMode<Error = E>,
+    DRIVE: DriveMode,
+{
+    /// Change the drive mode.
+    ///
+    /// NOTE: When changing to a new mode with a lower sample rate,
+    /// place the device in `Idle` mode for at least 10 minutes before
+    /// enabling the new mode.
+    #[allow(clippy::type_complexity)]
+    pub fn into_mode<TDRIVE: DriveMode>(
+        mut self,
+    ) -> Result<Ccs811Drive<DEV, TDRIVE>, ModeChangeError<E, Self>> {
+        match self.dev.set_mode(TDRIVE::MODE) {
+            Ok(()) => Ok(Ccs811Drive {
+                dev: self.dev,
+                _drive: PhantomData,
+            }),
+            Err(error) => Err(ModeChangeError::new(self, error)),
+        }
+    }
+
+    /// Get the measurement mode of the device.
+    pub fn mode(&self) -> MeasurementMode {
+        DRIVE::MODE
+    }
+
+    /// Get the current baseline.
+    pub fn baseline(&mut self) -> Result<[u8; 2], E> {
+        self.dev.baseline()
+    }
+
+    /// Set the baseline.
+    pub fn set_baseline(&mut self, baseline: [u8; 2]) -> Result<(), E> {
+        self.dev.set_baseline(baseline)
+    }
+
+    /// Set the environment temperature and relative humidity.
+    ///
+    /// See `Ccs811AppMode::set_environment()`.
+    pub fn set_environment(
+        &mut self,
+        humidity_percentage: f32,
+        temperature_celsius: f32,
+    ) -> Result<(), E> {
+        self.dev
+            .set_environment(humidity_percentage, temperature_celsius)
+    }
+}
+
+impl<DEV, DRIVE, E> Ccs811Drive<DEV, DRIVE>
+where
+    DEV: Ccs811AppMode<Error = E>,
+    DRIVE: AlgorithmDriveMode,
+{
+    /// Check if there is a new data sample ready.
+    pub fn has_data_ready(&mut self) -> Result<bool, E> {
+        self.dev.has_data_ready()
+    }
+
+    /// Get the algorithm results data.
+    pub fn data(&mut self) -> nb::Result<AlgorithmResult, E> {
+        self.dev.data()
+    }
+}
+
+impl<DEV, E> Ccs811Drive<DEV, drive::ConstantPower250ms>
+where
+    DEV: Ccs811AppMode<Error = E>,
+{
+    /// Check if there is a new raw data sample ready.
+    pub fn has_data_ready(&mut self) -> Result<bool, E> {
+        self.dev.has_data_ready()
+    }
+
+    /// Get the raw sensor data: (current, voltage).
+    ///
+    /// See `Ccs811AppMode::raw_data()`.
+    pub fn raw_data(&mut self) -> Result<(u8, u16), E> {
+        self.dev.raw_data()
+    }
+}
@@ -0,0 +1,431 @@
+use crate::filter::round;
+use crate::{AlgorithmResult, EmaFilter, ReadingFilter};
+
+/// Configuration of a `VocEventDetector`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VocEventConfig {
+    /// Time constant (ms) of the background adaptation. The background is
+    /// not adapted during an excursion. Default: 1 hour.
+    pub background_time_constant_ms: u32,
+    /// eTVOC rise over the background (ppb) starting an excursion.
+    /// Default: 100 ppb.
+    pub etvoc_threshold: Option<u16>,
+    /// eCO2 rise over the background (ppm) starting an excursion.
+    /// Default: 200 ppm.
+    pub eco2_threshold: Option<u16>,
+    /// Time (ms) the rise must last before the onset is reported.
+    /// Default: 30 seconds.
+    pub min_duration_ms: u32,
+    /// Time (ms) the values must stay below half the thresholds before the
+    /// end is reported. Default: 1 minute.
+    pub end_duration_ms: u32,
+    /// Maximum time (ms) an excursion may last. After it, the end is reported
+    /// and the current reading becomes the background, so that a lasting step
+    /// change does not keep the excursion going forever. Default: 4 hours.
+    pub max_duration_ms: u32,
+}
+
+impl Default for VocEventConfig {
+    fn default() -> Self {
+        VocEventConfig {
+            background_time_constant_ms: 60 * 60 * 1000,
+            etvoc_threshold: Some(100),
+            eco2_threshold: Some(200),
+            min_duration_ms: 30_000,
+            end_duration_ms: 60_000,
+            max_duration_ms: 4 * 60 * 60 * 1000,
+        }
+    }
+}
+
+/// Event reported by a `VocEventDetector`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum VocEvent {
+    /// An excursion started.
+    Onset {
+        /// Time (ms) at which the values rose over the thresholds
+        time_ms: u32,
+        /// eCO2 (ppm) when the onset was confirmed
+        eco2: u16,
+        /// eTVOC (ppb) when the onset was confirmed
+        etvoc: u16,
+    },
+    /// The excursion peaked and the values decayed to half the peak rise.
+    Peak {
+        /// Time (ms) of the peak
+        time_ms: u32,
+        /// eCO2 (ppm) at the peak
+        eco2: u16,
+        /// eTVOC (ppb) at the peak
+        etvoc: u16,
+    },
+    /// The excursion ended.
+    End {
+        /// Time (ms) at which the excursion started
+        start_ms: u32,
+        /// Time (ms) at which the values fell below half the thresholds or
+        /// the maximum duration elapsed
+        time_ms: u32,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Peak {
+    time_ms: u32,
+    eco2: u16,
+    etvoc: u16,
+    score: u32,
+    reported: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum State {
+    Idle,
+    Rising {
+        since_ms: u32,
+    },
+    Active {
+        start_ms: u32,
+        peak: Peak,
+        below_since_ms: Option<u32>,
+    },
+}
+
+/// Detector of eTVOC and eCO2 excursions such as cooking, cleaning sprays
+/// or occupancy.
+///
+/// The readings are compared against a slowly adapting background.
+/// An excursion starts when the rise over the background exceeds any of the
+/// thresholds for the minimum duration, and ends when the rise stays below
+/// half the thresholds for the end duration or after the maximum duration.
+///
+/// The readings are fed together with a timestamp in milliseconds from
+/// any monotonic clock. The timestamps may wrap around.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VocEventDetector {
+    config: VocEventConfig,
+    background: EmaFilter,
+    state: State,
+}
+
+impl VocEventDetector {
+    /// Create a new detector.
+    pub fn new(config: VocEventConfig) -> Self {
+        VocEventDetector {
+            config,
+            background: EmaFilter::new(config.background_time_constant_ms),
+            state: State::Idle,
+        }
+    }
+
+    /// Get the current background: (eCO2, eTVOC).
+    pub fn background(&self) -> Option<(u16, u16)> {
+        self.background
+            .average()
+            .map(|(eco2, etvoc)| (round(eco2), round(etvoc)))
+    }
+
+    /// Whether an excursion is ongoing.
+    pub fn is_active(&self) -> bool {
+        matches!(self.state, State::Active { .. })
+    }
+
+    /// Forget the background and any ongoing excursion.
+    pub fn reset(&mut self) {
+        self.background.reset();
+        self.state = State::Idle;
+    }
+
+    /// Feed a new reading.
+    ///
+    /// At most one event is reported per reading.
+    pub fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> Option<VocEvent> {
+        let score = self.score(result);
+        match self.state {
+            State::Idle => {
+                if score >= SCORE_THRESHOLD {
+                    self.state = State::Rising { since_ms: now_ms };
+                    return self.update(result, now_ms);
+                }
+                self.background.update(result, now_ms);
+                None
+            }
+            State::Rising { since_ms } => {
+                if score < SCORE_THRESHOLD {
+                    self.state = State::Idle;
+                    self.background.update(result, now_ms);
+                    None
+                } else if now_ms.wrapping_sub(since_ms) >= self.config.min_duration_ms {
+                    self.state = State::Active {
+                        start_ms: since_ms,
+                        peak: Peak {
+                            time_ms: now_ms,
+                            eco2: result.eco2,
+                            etvoc: result.etvoc,
+                            score,
+                            reported: false,
+                        },
+                        below_since_ms: None,
+                    };
+                    Some(VocEvent::Onset {
+                        time_ms: since_ms,
+                        eco2: result.eco2,
+                        etvoc: result.etvoc,
+                    })
+                } else {
+                    None
+                }
+            }
+            State::Active {
+                start_ms,
+                mut peak,
+                mut below_since_ms,
+            } => {
+                if now_ms.wrapping_sub(start_ms) >= self.config.max_duration_ms {
+                    self.state = State::Idle;
+                    self.background.reset();
+                    self.background.update(result, now_ms);
+                    return Some(VocEvent::End {
+                        start_ms,
+                        time_ms: now_ms,
+                    });
+                }
+                let mut event = None;
+                if score > peak.score {
+                    peak = Peak {
+                        time_ms: now_ms,
+                        eco2: result.eco2,
+                        etvoc: result.etvoc,
+                        score,
+                        reported: false,
+                    };
+                } else if !peak.reported && score <= peak.score / 2 {
+                    peak.reported = true;
+                    event = Some(VocEvent::Peak {
+                        time_ms: peak.time_ms,
+                        eco2: peak.eco2,
+                        etvoc: peak.etvoc,
+                    });
+                }
+                if score < SCORE_THRESHOLD / 2 {
+                    let since = *below_since_ms.get_or_insert(now_ms);
+                    let ended = now_ms.wrapping_sub(since) >= self.config.end_duration_ms;
+                    if ended && event.is_none() && peak.reported {
+                        self.state = State::Idle;
+                        // the excursion time does not count for the background
+                        self.background.restart(now_ms);
+                        return Some(VocEvent::End {
+                            start_ms,
+                            time_ms: since,
+                        });
+                    }
+                } else {
+                    below_since_ms = None;
+                }
+                self.state = State::Active {
+                    start_ms,
+                    peak,
+                    below_since_ms,
+                };
+                event
+            }
+        }
+    }
+
+    /// Rise over the background relative to the thresholds where
+    /// `SCORE_THRESHOLD` means the rise equals a threshold.
+    fn score(&self, result: &AlgorithmResult) -> u32 {
+        let (background_eco2, background_etvoc) = match self.background.average() {
+            Some(background) => background,
+            None => return 0,
+        };
+        let relative = |value: u16, background: f32, threshold: Option<u16>| match threshold {
+            Some(threshold) if f32::from(value) > background => {
+                let rise = f32::from(value) - background;
+                (rise * SCORE_THRESHOLD as f32 / f32::from(threshold.max(1))) as u32
+            }
+            _ => 0,
+        };
+        relative(result.eco2, background_eco2, self.config.eco2_threshold).max(relative(
+            result.etvoc,
+            background_etvoc,
+            self.config.etvoc_threshold,
+        ))
+    }
+}
+
+const SCORE_THRESHOLD: u32 = 1000;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(eco2: u16, etvoc: u16) -> AlgorithmResult {
+        AlgorithmResult {
+            eco2,
+            etvoc,
+            ..Default::default()
+        }
+    }
+
+    fn config() -> VocEventConfig {
+        VocEventConfig {
+            min_duration_ms: 2000,
+            end_duration_ms: 2000,
+            ..Default::default()
+        }
+    }
+
+    /// Feed one reading per second and get the event reported for each.
+    fn feed(
+        detector: &mut VocEventDetector,
+        values: &[(u16, u16)],
+        start_ms: u32,
+    ) -> [Option<VocEvent>; 10] {
+        let mut events = [None; 10];
+        for (i, &(eco2, etvoc)) in values.iter().enumerate() {
+            let now_ms = start_ms.wrapping_add(i as u32 * 1000);
+            events[i] = detector.update(&result(eco2, etvoc), now_ms);
+        }
+        events
+    }
+
+    #[test]
+    fn no_events_on_stable_readings() {
+        let mut detector = VocEventDetector::new(config());
+        assert_eq!([None; 10], feed(&mut detector, &[(450, 20); 10], 0));
+        assert_eq!(Some((450, 20)), detector.background());
+        assert!(!detector.is_active());
+    }
+
+    #[test]
+    fn reports_onset_peak_and_end() {
+        let mut detector = VocEventDetector::new(config());
+        let values = [
+            (450, 20),  // 0
+            (450, 20),  // 1000
+            (500, 150), // 2000: rise
+            (550, 300), // 3000
+            (600, 500), // 4000: onset confirmed
+            (550, 400), // 5000
+            (500, 200), // 6000: peak reported
+            (460, 40),  // 7000: below half thresholds
+            (450, 30),  // 8000
+            (450, 20),  // 9000: end
+        ];
+        let events = feed(&mut detector, &values, 0);
+        assert_eq!(
+            [
+                None,
+                None,
+                None,
+                None,
+                Some(VocEvent::Onset {
+                    time_ms: 2000,
+                    eco2: 600,
+                    etvoc: 500,
+                }),
+                None,
+                Some(VocEvent::Peak {
+                    time_ms: 4000,
+                    eco2: 600,
+                    etvoc: 500,
+                }),
+                None,
+                None,
+                Some(VocEvent::End {
+                    start_ms: 2000,
+                    time_ms: 7000,
+                }),
+            ],
+            events
+        );
+        assert!(!detector.is_active());
+        assert_eq!(Some((450, 20)), detector.background());
+    }
+
+    #[test]
+    fn short_rises_are_ignored() {
+        let mut detector = VocEventDetector::new(config());
+        let values = [(450, 20), (450, 300), (450, 300), (450, 20), (450, 20)];
+        assert_eq!([None; 10], feed(&mut detector, &values, 0));
+    }
+
+    #[test]
+    fn eco2_rise_starts_excursion() {
+        let mut detector = VocEventDetector::new(config());
+        let values = [(450, 20), (700, 20), (700, 20), (700, 20)];
+        let events = feed(&mut detector, &values, 0);
+        assert_eq!(
+            Some(VocEvent::Onset {
+                time_ms: 1000,
+                eco2: 700,
+                etvoc: 20,
+            }),
+            events[3]
+        );
+        assert!(detector.is_active());
+    }
+
+    #[test]
+    fn step_change_ends_after_max_duration() {
+        let mut detector = VocEventDetector::new(VocEventConfig {
+            max_duration_ms: 5000,
+            ..config()
+        });
+        let mut values = [(700, 20); 10];
+        values[0] = (450, 20);
+        let events = feed(&mut detector, &values, 0);
+        assert!(events[3].is_some());
+        assert_eq!(
+            Some(VocEvent::End {
+                start_ms: 1000,
+                time_ms: 6000,
+            }),
+            events[6]
+        );
+        assert_eq!([None; 3], events[7..]);
+        assert!(!detector.is_active());
+        assert_eq!(Some((700, 20)), detector.background());
+    }
+
+    #[test]
+    fn thresholds_can_be_disabled() {
+        let mut detector = VocEventDetector::new(VocEventConfig {
+            eco2_threshold: None,
+            ..config()
+        });
+        let values = [(450, 20), (900, 20), (900, 20), (900, 20)];
+        assert_eq!([None; 10], feed(&mut detector, &values, 0));
+    }
+
+    #[test]
+    fn background_adapts_slowly() {
+        let mut detector = VocEventDetector::new(VocEventConfig {
+            background_time_constant_ms: 1000,
+            ..config()
+        });
+        detector.update(&result(400, 0), 0);
+        detector.update(&result(500, 50), 1000);
+        assert_eq!(Some((450, 25)), detector.background());
+    }
+
+    #[test]
+    fn handles_timestamp_wrap() {
+        let mut detector = VocEventDetector::new(config());
+        let values = [(450, 20), (450, 300), (450, 300), (450, 300)];
+        let events = feed(&mut detector, &values, u32::MAX - 1500);
+        assert!(events[3].is_some());
+    }
+
+    #[test]
+    fn can_reset() {
+        let mut detector = VocEventDetector::new(config());
+        let values = [(450, 20), (450, 300), (450, 300), (450, 300)];
+        feed(&mut detector, &values, 0);
+        assert!(detector.is_active());
+        detector.reset();
+        assert!(!detector.is_active());
+        assert_eq!(None, detector.background());
+    }
+}
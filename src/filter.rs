@@ -0,0 +1,342 @@
+use crate::AlgorithmResult;
+
+/// Smoothing filter over the eCO2 and eTVOC readings.
+///
+/// The filter parameters are expressed in time so that they stay valid when
+/// the measurement mode and therefore the sample rate changes.
+/// The readings are fed together with a timestamp in milliseconds from
+/// any monotonic clock. The timestamps may wrap around.
+///
+/// Filters can be chained as a tuple `(first, second)`.
+pub trait ReadingFilter {
+    /// Feed a new reading and get the filtered result.
+    ///
+    /// Only the eCO2 and eTVOC values are filtered. The raw data is
+    /// passed through.
+    fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> AlgorithmResult;
+
+    /// Forget all previous readings.
+    fn reset(&mut self);
+
+    /// Filter the result of `Ccs811AppMode::data()`.
+    ///
+    /// Errors are passed through.
+    fn filter<E>(
+        &mut self,
+        result: nb::Result<AlgorithmResult, E>,
+        now_ms: u32,
+    ) -> nb::Result<AlgorithmResult, E> {
+        result.map(|result| self.update(&result, now_ms))
+    }
+}
+
+impl<A: ReadingFilter, B: ReadingFilter> ReadingFilter for (A, B) {
+    fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> AlgorithmResult {
+        let result = self.0.update(result, now_ms);
+        self.1.update(&result, now_ms)
+    }
+
+    fn reset(&mut self) {
+        self.0.reset();
+        self.1.reset();
+    }
+}
+
+fn with_values(result: &AlgorithmResult, eco2: u16, etvoc: u16) -> AlgorithmResult {
+    AlgorithmResult {
+        eco2,
+        etvoc,
+        ..*result
+    }
+}
+
+/// Round to the nearest value, saturating at the `u16` range.
+pub(crate) fn round(value: f32) -> u16 {
+    if value <= 0.0 {
+        0
+    } else if value >= f32::from(u16::MAX) {
+        u16::MAX
+    } else {
+        (value + 0.5) as u16
+    }
+}
+
+/// Exponential moving average filter.
+///
+/// The weight of each reading is `dt / (time_constant + dt)` where `dt` is
+/// the time elapsed since the previous reading.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EmaFilter {
+    time_constant_ms: u32,
+    state: Option<(f32, f32, u32)>,
+}
+
+impl EmaFilter {
+    /// Create a new filter with the given time constant.
+    pub fn new(time_constant_ms: u32) -> Self {
+        EmaFilter {
+            time_constant_ms,
+            state: None,
+        }
+    }
+
+    /// Get the current average: (eCO2, eTVOC).
+    pub(crate) fn average(&self) -> Option<(f32, f32)> {
+        self.state.map(|(eco2, etvoc, _)| (eco2, etvoc))
+    }
+
+    /// Restart the time elapsed since the previous reading.
+    pub(crate) fn restart(&mut self, now_ms: u32) {
+        if let Some((_, _, last_ms)) = self.state.as_mut() {
+            *last_ms = now_ms;
+        }
+    }
+}
+
+impl ReadingFilter for EmaFilter {
+    fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> AlgorithmResult {
+        let (eco2, etvoc) = match self.state {
+            None => (f32::from(result.eco2), f32::from(result.etvoc)),
+            Some((eco2, etvoc, last_ms)) => {
+                let dt = now_ms.wrapping_sub(last_ms) as f32;
+                let total = self.time_constant_ms as f32 + dt;
+                let alpha = if total > 0.0 { dt / total } else { 1.0 };
+                (
+                    eco2 + alpha * (f32::from(result.eco2) - eco2),
+                    etvoc + alpha * (f32::from(result.etvoc) - etvoc),
+                )
+            }
+        };
+        self.state = Some((eco2, etvoc, now_ms));
+        with_values(result, round(eco2), round(etvoc))
+    }
+
+    fn reset(&mut self) {
+        self.state = None;
+    }
+}
+
+/// Median filter over the readings of a time window.
+///
+/// At most the last `N` readings within the window are kept.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MedianFilter<const N: usize> {
+    window_ms: u32,
+    samples: [(u32, u16, u16); N],
+    len: usize,
+    next: usize,
+}
+
+impl<const N: usize> MedianFilter<N> {
+    /// Create a new filter with the given window.
+    ///
+    /// Panics if `N` is zero.
+    pub fn new(window_ms: u32) -> Self {
+        assert!(N > 0);
+        MedianFilter {
+            window_ms,
+            samples: [(0, 0, 0); N],
+            len: 0,
+            next: 0,
+        }
+    }
+}
+
+impl<const N: usize> ReadingFilter for MedianFilter<N> {
+    fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> AlgorithmResult {
+        self.samples[self.next] = (now_ms, result.eco2, result.etvoc);
+        self.next = (self.next + 1) % N;
+        self.len = (self.len + 1).min(N);
+        let mut eco2 = [0; N];
+        let mut etvoc = [0; N];
+        let mut count = 0;
+        for &(time_ms, sample_eco2, sample_etvoc) in &self.samples[..self.len] {
+            if now_ms.wrapping_sub(time_ms) <= self.window_ms {
+                eco2[count] = sample_eco2;
+                etvoc[count] = sample_etvoc;
+                count += 1;
+            }
+        }
+        with_values(
+            result,
+            median(&mut eco2[..count]),
+            median(&mut etvoc[..count]),
+        )
+    }
+
+    fn reset(&mut self) {
+        self.len = 0;
+        self.next = 0;
+    }
+}
+
+fn median(values: &mut [u16]) -> u16 {
+    values.sort_unstable();
+    let middle = values.len() / 2;
+    if values.len() % 2 == 0 {
+        ((u32::from(values[middle - 1]) + u32::from(values[middle])) / 2) as u16
+    } else {
+        values[middle]
+    }
+}
+
+/// Filter rejecting sudden spikes.
+///
+/// Readings which changed from the last accepted reading faster than the
+/// given rates are replaced by the last accepted reading. If readings keep
+/// being rejected for longer than `accept_after_ms`, the new level is
+/// accepted so that real step changes are followed.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpikeFilter {
+    max_eco2_rate: u16,
+    max_etvoc_rate: u16,
+    accept_after_ms: u32,
+    last: Option<(u16, u16, u32)>,
+    rejecting_since_ms: Option<u32>,
+}
+
+impl SpikeFilter {
+    /// Create a new filter.
+    ///
+    /// The maximum rates are given in ppm/s for eCO2 and ppb/s for eTVOC.
+    pub fn new(max_eco2_rate: u16, max_etvoc_rate: u16, accept_after_ms: u32) -> Self {
+        SpikeFilter {
+            max_eco2_rate,
+            max_etvoc_rate,
+            accept_after_ms,
+            last: None,
+            rejecting_since_ms: None,
+        }
+    }
+}
+
+fn exceeds_rate(last: u16, value: u16, rate: u16, dt_ms: u32) -> bool {
+    let allowed = u64::from(rate) * u64::from(dt_ms) / 1000;
+    u64::from(last.abs_diff(value)) > allowed
+}
+
+impl ReadingFilter for SpikeFilter {
+    fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> AlgorithmResult {
+        if let Some((eco2, etvoc, last_ms)) = self.last {
+            let dt = now_ms.wrapping_sub(last_ms);
+            let spike = exceeds_rate(eco2, result.eco2, self.max_eco2_rate, dt)
+                || exceeds_rate(etvoc, result.etvoc, self.max_etvoc_rate, dt);
+            if spike {
+                let since = *self.rejecting_since_ms.get_or_insert(now_ms);
+                if now_ms.wrapping_sub(since) < self.accept_after_ms {
+                    return with_values(result, eco2, etvoc);
+                }
+            }
+        }
+        self.last = Some((result.eco2, result.etvoc, now_ms));
+        self.rejecting_since_ms = None;
+        *result
+    }
+
+    fn reset(&mut self) {
+        self.last = None;
+        self.rejecting_since_ms = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(eco2: u16, etvoc: u16) -> AlgorithmResult {
+        AlgorithmResult {
+            eco2,
+            etvoc,
+            raw_current: 10,
+            raw_voltage: 500,
+        }
+    }
+
+    #[test]
+    fn ema_starts_at_first_reading() {
+        let mut filter = EmaFilter::new(10_000);
+        assert_eq!(result(500, 20), filter.update(&result(500, 20), 0));
+    }
+
+    #[test]
+    fn ema_weight_depends_on_elapsed_time() {
+        let mut filter = EmaFilter::new(1000);
+        filter.update(&result(400, 0), 0);
+        assert_eq!(result(500, 50), filter.update(&result(600, 100), 1000));
+        let mut filter = EmaFilter::new(1000);
+        filter.update(&result(400, 0), 0);
+        assert_eq!(result(580, 90), filter.update(&result(600, 100), 9000));
+    }
+
+    #[test]
+    fn ema_can_be_reset() {
+        let mut filter = EmaFilter::new(1000);
+        filter.update(&result(400, 0), 0);
+        filter.reset();
+        assert_eq!(result(600, 100), filter.update(&result(600, 100), 1000));
+    }
+
+    #[test]
+    fn median_of_window() {
+        let mut filter = MedianFilter::<5>::new(2000);
+        filter.update(&result(400, 10), 0);
+        filter.update(&result(2000, 500), 1000);
+        assert_eq!(result(500, 30), filter.update(&result(500, 30), 2000));
+        assert_eq!(result(550, 40), filter.update(&result(600, 50), 3500));
+    }
+
+    #[test]
+    fn median_keeps_last_n_readings() {
+        let mut filter = MedianFilter::<3>::new(10_000);
+        filter.update(&result(400, 0), 0);
+        filter.update(&result(400, 0), 1);
+        filter.update(&result(800, 10), 2);
+        filter.update(&result(800, 10), 3);
+        assert_eq!(result(800, 10), filter.update(&result(400, 0), 4));
+    }
+
+    #[test]
+    fn spike_is_rejected() {
+        let mut filter = SpikeFilter::new(100, 50, 5000);
+        filter.update(&result(400, 10), 0);
+        assert_eq!(result(450, 30), filter.update(&result(450, 30), 1000));
+        assert_eq!(result(450, 30), filter.update(&result(2000, 30), 2000));
+        assert_eq!(result(500, 30), filter.update(&result(500, 30), 3000));
+    }
+
+    #[test]
+    fn spike_rates_scale_with_elapsed_time() {
+        let mut filter = SpikeFilter::new(100, 50, 5000);
+        filter.update(&result(400, 10), 0);
+        assert_eq!(result(1000, 300), filter.update(&result(1000, 300), 60_000));
+    }
+
+    #[test]
+    fn persistent_change_is_accepted() {
+        let mut filter = SpikeFilter::new(100, 50, 2000);
+        filter.update(&result(400, 10), 0);
+        assert_eq!(result(400, 10), filter.update(&result(2000, 10), 1000));
+        assert_eq!(result(400, 10), filter.update(&result(2000, 10), 2000));
+        assert_eq!(result(2000, 10), filter.update(&result(2000, 10), 3000));
+        assert_eq!(result(2050, 10), filter.update(&result(2050, 10), 4000));
+    }
+
+    #[test]
+    fn filters_can_be_chained() {
+        let mut filter = (SpikeFilter::new(100, 50, 5000), EmaFilter::new(1000));
+        filter.update(&result(400, 0), 0);
+        assert_eq!(result(400, 0), filter.update(&result(3000, 0), 1000));
+        assert_eq!(result(450, 0), filter.update(&result(500, 0), 2000));
+    }
+
+    #[test]
+    fn passes_errors_through() {
+        let mut filter = EmaFilter::new(1000);
+        let error: nb::Result<AlgorithmResult, ()> = Err(nb::Error::WouldBlock);
+        assert!(filter.filter(error, 0).is_err());
+        assert_eq!(
+            Ok(result(400, 0)),
+            filter.filter::<()>(Ok(result(400, 0)), 0)
+        );
+    }
+}
@@ -0,0 +1,298 @@
+use crate::{AlgorithmResult, DeviceErrors, MeasurementMode};
+
+/// Minimum eCO2 value in the device specification (ppm)
+const ECO2_MIN: u16 = 400;
+/// Maximum eCO2 value in the device specification (ppm)
+const ECO2_MAX: u16 = 8192;
+/// Maximum eTVOC value in the device specification (ppb)
+const ETVOC_MAX: u16 = 1187;
+
+/// Checks done by a `HealthMonitor`.
+///
+/// Each check can be disabled.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthPolicy {
+    /// Time (ms) after which unchanged eCO2 and eTVOC values are considered stuck.
+    ///
+    /// Note that in clean air the device can report 400 ppm eCO2 and 0 ppb
+    /// eTVOC for a long time. Default: 2 hours.
+    pub stuck_after_ms: Option<u32>,
+    /// Check that the eCO2 is within 400-8192 ppm and the eTVOC within
+    /// 0-1187 ppb. Default: enabled.
+    pub check_range: bool,
+    /// Number of consecutive maximum sensor resistance errors which are
+    /// considered a failure. Default: 3.
+    pub max_resistance_count: Option<u16>,
+    /// Time (ms) exceeding the measurement interval after which missing
+    /// data is reported. Default: 1 second.
+    pub missing_data_margin_ms: Option<u32>,
+}
+
+impl Default for HealthPolicy {
+    fn default() -> Self {
+        HealthPolicy {
+            stuck_after_ms: Some(2 * 60 * 60 * 1000),
+            check_range: true,
+            max_resistance_count: Some(3),
+            missing_data_margin_ms: Some(1000),
+        }
+    }
+}
+
+/// Health state reported by a `HealthMonitor`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct HealthStatus {
+    /// The values did not change for longer than allowed.
+    pub stuck: bool,
+    /// The last values were out of the device specification.
+    pub out_of_range: bool,
+    /// Repeated maximum sensor resistance errors were reported.
+    pub max_resistance: bool,
+    /// No new data was available for longer than the measurement interval.
+    pub missing_data: bool,
+}
+
+impl HealthStatus {
+    /// Whether no problem was detected.
+    pub fn is_healthy(&self) -> bool {
+        !(self.stuck || self.out_of_range || self.max_resistance || self.missing_data)
+    }
+}
+
+/// Health monitor over the algorithm results of a device.
+///
+/// The readings and device errors are fed to the monitor together with
+/// a timestamp in milliseconds from any monotonic clock. The timestamps
+/// may wrap around.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HealthMonitor {
+    policy: HealthPolicy,
+    mode: MeasurementMode,
+    status: HealthStatus,
+    last: Option<(u16, u16)>,
+    unchanged_since_ms: u32,
+    max_resistance_count: u16,
+    last_data_ms: Option<u32>,
+}
+
+impl HealthMonitor {
+    /// Create a new monitor for a device in the given measurement mode.
+    pub fn new(mode: MeasurementMode, policy: HealthPolicy) -> Self {
+        HealthMonitor {
+            policy,
+            mode,
+            status: HealthStatus::default(),
+            last: None,
+            unchanged_since_ms: 0,
+            max_resistance_count: 0,
+            last_data_ms: None,
+        }
+    }
+
+    /// Set the measurement mode the device was configured to.
+    ///
+    /// This restarts the missing data check.
+    pub fn set_mode(&mut self, mode: MeasurementMode, now_ms: u32) {
+        self.mode = mode;
+        self.last_data_ms = Some(now_ms);
+        self.status.missing_data = false;
+    }
+
+    /// Get the current health state.
+    pub fn status(&self) -> HealthStatus {
+        self.status
+    }
+
+    /// Feed a new reading.
+    pub fn on_data(&mut self, result: &AlgorithmResult, now_ms: u32) -> HealthStatus {
+        let values = (result.eco2, result.etvoc);
+        if self.last != Some(values) {
+            self.last = Some(values);
+            self.unchanged_since_ms = now_ms;
+        }
+        self.status.stuck = match self.policy.stuck_after_ms {
+            Some(limit) => now_ms.wrapping_sub(self.unchanged_since_ms) > limit,
+            None => false,
+        };
+        self.status.out_of_range = self.policy.check_range
+            && (result.eco2 < ECO2_MIN || result.eco2 > ECO2_MAX || result.etvoc > ETVOC_MAX);
+        self.max_resistance_count = 0;
+        self.status.max_resistance = false;
+        self.last_data_ms = Some(now_ms);
+        self.status.missing_data = false;
+        self.status
+    }
+
+    /// Feed the errors reported by the device.
+    pub fn on_device_errors(&mut self, errors: &DeviceErrors, now_ms: u32) -> HealthStatus {
+        if errors.max_resistance {
+            self.max_resistance_count = self.max_resistance_count.saturating_add(1);
+        } else {
+            self.max_resistance_count = 0;
+        }
+        self.status.max_resistance = match self.policy.max_resistance_count {
+            Some(limit) => self.max_resistance_count >= limit,
+            None => false,
+        };
+        self.check(now_ms)
+    }
+
+    /// Check whether data is missing at the given time.
+    ///
+    /// This should be called when the device has no new data.
+    /// The first call starts the check if no data was fed yet.
+    pub fn check(&mut self, now_ms: u32) -> HealthStatus {
+        let last_data_ms = *self.last_data_ms.get_or_insert(now_ms);
+        self.status.missing_data =
+            match (self.policy.missing_data_margin_ms, interval_ms(self.mode)) {
+                (Some(margin), Some(interval)) => {
+                    now_ms.wrapping_sub(last_data_ms) > interval.saturating_add(margin)
+                }
+                _ => false,
+            };
+        self.status
+    }
+}
+
+fn interval_ms(mode: MeasurementMode) -> Option<u32> {
+    match mode {
+        MeasurementMode::Idle => None,
+        MeasurementMode::ConstantPower1s => Some(1000),
+        MeasurementMode::PulseHeating10s => Some(10_000),
+        MeasurementMode::LowPowerPulseHeating60s => Some(60_000),
+        MeasurementMode::ConstantPower250ms => Some(250),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(eco2: u16, etvoc: u16) -> AlgorithmResult {
+        AlgorithmResult {
+            eco2,
+            etvoc,
+            ..Default::default()
+        }
+    }
+
+    fn new_monitor() -> HealthMonitor {
+        HealthMonitor::new(MeasurementMode::ConstantPower1s, HealthPolicy::default())
+    }
+
+    #[test]
+    fn healthy_readings() {
+        let mut monitor = new_monitor();
+        assert!(monitor.on_data(&result(450, 10), 0).is_healthy());
+        assert!(monitor.on_data(&result(460, 12), 1000).is_healthy());
+        assert!(monitor.check(1500).is_healthy());
+    }
+
+    #[test]
+    fn detects_stuck_values() {
+        let policy = HealthPolicy {
+            stuck_after_ms: Some(5000),
+            ..Default::default()
+        };
+        let mut monitor = HealthMonitor::new(MeasurementMode::ConstantPower1s, policy);
+        monitor.on_data(&result(450, 10), 0);
+        assert!(!monitor.on_data(&result(450, 10), 5000).stuck);
+        assert!(monitor.on_data(&result(450, 10), 5001).stuck);
+        assert!(!monitor.on_data(&result(451, 10), 6000).stuck);
+    }
+
+    #[test]
+    fn detects_stuck_values_across_timestamp_wrap() {
+        let policy = HealthPolicy {
+            stuck_after_ms: Some(5000),
+            ..Default::default()
+        };
+        let mut monitor = HealthMonitor::new(MeasurementMode::ConstantPower1s, policy);
+        monitor.on_data(&result(450, 10), u32::MAX - 1000);
+        assert!(monitor.on_data(&result(450, 10), 4500).stuck);
+    }
+
+    #[test]
+    fn can_disable_stuck_check() {
+        let policy = HealthPolicy {
+            stuck_after_ms: None,
+            ..Default::default()
+        };
+        let mut monitor = HealthMonitor::new(MeasurementMode::ConstantPower1s, policy);
+        monitor.on_data(&result(450, 10), 0);
+        assert!(!monitor.on_data(&result(450, 10), 100_000_000).stuck);
+    }
+
+    #[test]
+    fn detects_out_of_range_values() {
+        let mut monitor = new_monitor();
+        assert!(!monitor.on_data(&result(400, 0), 0).out_of_range);
+        assert!(!monitor.on_data(&result(8192, 1187), 1000).out_of_range);
+        assert!(monitor.on_data(&result(399, 0), 2000).out_of_range);
+        assert!(monitor.on_data(&result(8193, 0), 3000).out_of_range);
+        assert!(monitor.on_data(&result(500, 1188), 4000).out_of_range);
+    }
+
+    #[test]
+    fn can_disable_range_check() {
+        let policy = HealthPolicy {
+            check_range: false,
+            ..Default::default()
+        };
+        let mut monitor = HealthMonitor::new(MeasurementMode::ConstantPower1s, policy);
+        assert!(!monitor.on_data(&result(0, 0), 0).out_of_range);
+    }
+
+    #[test]
+    fn detects_repeated_max_resistance_errors() {
+        let mut monitor = new_monitor();
+        let errors = DeviceErrors {
+            max_resistance: true,
+            ..Default::default()
+        };
+        monitor.on_data(&result(450, 10), 0);
+        assert!(!monitor.on_device_errors(&errors, 500).max_resistance);
+        assert!(!monitor.on_device_errors(&errors, 600).max_resistance);
+        assert!(monitor.on_device_errors(&errors, 700).max_resistance);
+        assert!(!monitor.on_data(&result(450, 10), 1000).max_resistance);
+    }
+
+    #[test]
+    fn other_errors_reset_max_resistance_count() {
+        let mut monitor = new_monitor();
+        let errors = DeviceErrors {
+            max_resistance: true,
+            ..Default::default()
+        };
+        monitor.on_device_errors(&errors, 0);
+        monitor.on_device_errors(&errors, 100);
+        monitor.on_device_errors(&DeviceErrors::default(), 200);
+        assert!(!monitor.on_device_errors(&errors, 300).max_resistance);
+    }
+
+    #[test]
+    fn detects_missing_data() {
+        let mut monitor = new_monitor();
+        monitor.on_data(&result(450, 10), 0);
+        assert!(!monitor.check(2000).missing_data);
+        assert!(monitor.check(2001).missing_data);
+        assert!(!monitor.on_data(&result(450, 10), 2500).missing_data);
+    }
+
+    #[test]
+    fn missing_data_check_starts_on_first_call() {
+        let mut monitor = new_monitor();
+        assert!(!monitor.check(10_000).missing_data);
+        assert!(monitor.check(12_001).missing_data);
+    }
+
+    #[test]
+    fn missing_data_uses_mode_interval() {
+        let mut monitor = new_monitor();
+        monitor.set_mode(MeasurementMode::PulseHeating10s, 0);
+        assert!(!monitor.check(11_000).missing_data);
+        assert!(monitor.check(11_001).missing_data);
+        monitor.set_mode(MeasurementMode::Idle, 11_001);
+        assert!(!monitor.check(100_000).missing_data);
+    }
+}
@@ -0,0 +1,388 @@
+use crate::{AlgorithmResult, DeviceErrors};
+
+/// Statistics of a value over a time window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    /// Minimum value
+    pub min: u16,
+    /// Maximum value
+    pub max: u16,
+    /// Mean value over all readings
+    pub mean: u16,
+}
+
+/// eCO2 and eTVOC statistics over a time window.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct HistoryStats {
+    /// Number of readings in the window
+    pub readings: u32,
+    /// eCO2 statistics (ppm)
+    pub eco2: Statistics,
+    /// eTVOC statistics (ppb)
+    pub etvoc: Statistics,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Accumulator {
+    sum: u32,
+    min: u16,
+    max: u16,
+}
+
+impl Accumulator {
+    const EMPTY: Accumulator = Accumulator {
+        sum: 0,
+        min: u16::MAX,
+        max: 0,
+    };
+
+    fn add(&mut self, value: u16) {
+        self.sum = self.sum.saturating_add(u32::from(value));
+        self.min = self.min.min(value);
+        self.max = self.max.max(value);
+    }
+
+    fn merge(&mut self, other: &Accumulator) {
+        self.sum = self.sum.saturating_add(other.sum);
+        self.min = self.min.min(other.min);
+        self.max = self.max.max(other.max);
+    }
+
+    fn statistics(&self, count: u32) -> Statistics {
+        Statistics {
+            min: self.min,
+            max: self.max,
+            mean: (self.sum / count) as u16,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Bucket {
+    count: u32,
+    eco2: Accumulator,
+    etvoc: Accumulator,
+}
+
+impl Bucket {
+    const EMPTY: Bucket = Bucket {
+        count: 0,
+        eco2: Accumulator::EMPTY,
+        etvoc: Accumulator::EMPTY,
+    };
+
+    fn means(&self) -> (u16, u16) {
+        (
+            (self.eco2.sum / self.count) as u16,
+            (self.etvoc.sum / self.count) as u16,
+        )
+    }
+}
+
+/// Rolling history of the algorithm results without allocation.
+///
+/// The readings are downsampled into `N` buckets of a fixed duration, so
+/// the history covers the last `N * bucket_ms` milliseconds. For example,
+/// 288 buckets of 5 minutes cover one day.
+///
+/// Readings taken during the warm-up time and readings flagged with
+/// device errors are skipped.
+///
+/// The readings are fed together with a timestamp in milliseconds from
+/// any monotonic clock. The timestamps may wrap around.
+#[derive(Debug, Clone, PartialEq)]
+pub struct History<const N: usize> {
+    buckets: [Bucket; N],
+    bucket_ms: u32,
+    current: usize,
+    current_start_ms: u32,
+    warm_up_ms: u32,
+    warm_up_start_ms: Option<u32>,
+    skipped: u32,
+}
+
+impl<const N: usize> History<N> {
+    /// Create a new history with buckets of `bucket_ms` milliseconds.
+    ///
+    /// Readings are skipped until `warm_up_ms` elapsed since `now_ms`.
+    ///
+    /// Panics if `N` or `bucket_ms` are zero.
+    pub fn new(bucket_ms: u32, warm_up_ms: u32, now_ms: u32) -> Self {
+        assert!(N > 0 && bucket_ms > 0);
+        History {
+            buckets: [Bucket::EMPTY; N],
+            bucket_ms,
+            current: 0,
+            current_start_ms: now_ms,
+            warm_up_ms,
+            warm_up_start_ms: Some(now_ms),
+            skipped: 0,
+        }
+    }
+
+    /// Start the warm-up time again, for example after a reset or a
+    /// measurement mode change.
+    pub fn restart_warm_up(&mut self, now_ms: u32) {
+        self.warm_up_start_ms = Some(now_ms);
+    }
+
+    /// Whether the warm-up time is still running.
+    pub fn is_warming_up(&self, now_ms: u32) -> bool {
+        match self.warm_up_start_ms {
+            Some(start) => now_ms.wrapping_sub(start) < self.warm_up_ms,
+            None => false,
+        }
+    }
+
+    /// Remove all readings.
+    pub fn clear(&mut self) {
+        self.buckets = [Bucket::EMPTY; N];
+    }
+
+    /// Number of readings skipped.
+    pub fn skipped(&self) -> u32 {
+        self.skipped
+    }
+
+    /// Feed a new reading.
+    ///
+    /// Returns whether it was recorded.
+    pub fn add(&mut self, result: &AlgorithmResult, now_ms: u32) -> bool {
+        self.advance(now_ms);
+        if self.is_warming_up(now_ms) {
+            self.skipped = self.skipped.saturating_add(1);
+            return false;
+        }
+        self.warm_up_start_ms = None;
+        let bucket = &mut self.buckets[self.current];
+        bucket.count += 1;
+        bucket.eco2.add(result.eco2);
+        bucket.etvoc.add(result.etvoc);
+        true
+    }
+
+    /// Feed the errors reported by the device instead of a reading.
+    ///
+    /// The reading is skipped.
+    pub fn on_device_errors(&mut self, _errors: &DeviceErrors, now_ms: u32) {
+        self.advance(now_ms);
+        self.skipped = self.skipped.saturating_add(1);
+    }
+
+    /// Get the statistics over the last `window_ms` milliseconds.
+    ///
+    /// The window is rounded up to whole buckets and includes the current
+    /// bucket. Returns `None` if there are no readings in the window.
+    pub fn stats(&self, window_ms: u32, now_ms: u32) -> Option<HistoryStats> {
+        let mut readings = 0;
+        let mut eco2 = Accumulator::EMPTY;
+        let mut etvoc = Accumulator::EMPTY;
+        for bucket in self.window(window_ms, now_ms) {
+            readings += bucket.count;
+            eco2.merge(&bucket.eco2);
+            etvoc.merge(&bucket.etvoc);
+        }
+        if readings == 0 {
+            None
+        } else {
+            Some(HistoryStats {
+                readings,
+                eco2: eco2.statistics(readings),
+                etvoc: etvoc.statistics(readings),
+            })
+        }
+    }
+
+    /// Get a percentile of the bucket means over the last `window_ms`
+    /// milliseconds: (eCO2, eTVOC).
+    ///
+    /// `percent` must be within 0-100. The nearest-rank method is used.
+    /// Returns `None` if there are no readings in the window or
+    /// `percent` is greater than 100.
+    pub fn percentile(&self, window_ms: u32, percent: u8, now_ms: u32) -> Option<(u16, u16)> {
+        if percent > 100 {
+            return None;
+        }
+        let mut eco2 = [0; N];
+        let mut etvoc = [0; N];
+        let mut count = 0;
+        for bucket in self.window(window_ms, now_ms) {
+            let (eco2_mean, etvoc_mean) = bucket.means();
+            eco2[count] = eco2_mean;
+            etvoc[count] = etvoc_mean;
+            count += 1;
+        }
+        if count == 0 {
+            return None;
+        }
+        let eco2 = &mut eco2[..count];
+        let etvoc = &mut etvoc[..count];
+        eco2.sort_unstable();
+        etvoc.sort_unstable();
+        let rank = (usize::from(percent) * count + 99) / 100;
+        let index = rank.max(1) - 1;
+        Some((eco2[index], etvoc[index]))
+    }
+
+    fn window(&self, window_ms: u32, now_ms: u32) -> impl Iterator<Item = &Bucket> {
+        let elapsed = now_ms.wrapping_sub(self.current_start_ms) / self.bucket_ms;
+        let size = window_ms / self.bucket_ms + u32::from(window_ms % self.bucket_ms != 0);
+        // Buckets which would be cleared by now are not included.
+        let size = (size.max(1) as usize).min(N);
+        let stale = (elapsed as usize).min(N);
+        let count = size.saturating_sub(stale);
+        let current = self.current;
+        (0..count)
+            .map(move |i| &self.buckets[(current + N - i) % N])
+            .filter(|bucket| bucket.count > 0)
+    }
+
+    fn advance(&mut self, now_ms: u32) {
+        let elapsed = now_ms.wrapping_sub(self.current_start_ms) / self.bucket_ms;
+        if elapsed == 0 {
+            return;
+        }
+        for i in 1..=(elapsed as usize).min(N) {
+            self.buckets[(self.current + i) % N] = Bucket::EMPTY;
+        }
+        self.current = (self.current + (elapsed as usize % N)) % N;
+        self.current_start_ms = self
+            .current_start_ms
+            .wrapping_add(elapsed.wrapping_mul(self.bucket_ms));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(eco2: u16, etvoc: u16) -> AlgorithmResult {
+        AlgorithmResult {
+            eco2,
+            etvoc,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn empty_history_has_no_stats() {
+        let history = History::<4>::new(1000, 0, 0);
+        assert_eq!(None, history.stats(4000, 0));
+        assert_eq!(None, history.percentile(4000, 50, 0));
+    }
+
+    #[test]
+    fn can_get_stats() {
+        let mut history = History::<4>::new(1000, 0, 0);
+        history.add(&result(400, 10), 0);
+        history.add(&result(600, 30), 500);
+        history.add(&result(800, 20), 1000);
+        let stats = history.stats(4000, 1000).unwrap();
+        assert_eq!(3, stats.readings);
+        assert_eq!(
+            Statistics {
+                min: 400,
+                max: 800,
+                mean: 600,
+            },
+            stats.eco2
+        );
+        assert_eq!(
+            Statistics {
+                min: 10,
+                max: 30,
+                mean: 20,
+            },
+            stats.etvoc
+        );
+    }
+
+    #[test]
+    fn window_is_rounded_up_to_buckets() {
+        let mut history = History::<4>::new(1000, 0, 0);
+        history.add(&result(400, 10), 0);
+        history.add(&result(800, 20), 1000);
+        assert_eq!(1, history.stats(1000, 1000).unwrap().readings);
+        assert_eq!(2, history.stats(1001, 1000).unwrap().readings);
+    }
+
+    #[test]
+    fn old_buckets_are_dropped() {
+        let mut history = History::<2>::new(1000, 0, 0);
+        history.add(&result(400, 10), 0);
+        history.add(&result(500, 10), 1000);
+        history.add(&result(600, 10), 2000);
+        let stats = history.stats(10_000, 2000).unwrap();
+        assert_eq!(2, stats.readings);
+        assert_eq!(500, stats.eco2.min);
+        assert_eq!(None, history.stats(10_000, 4000));
+    }
+
+    #[test]
+    fn handles_gaps_longer_than_history() {
+        let mut history = History::<3>::new(1000, 0, 0);
+        history.add(&result(400, 10), 0);
+        history.add(&result(700, 10), 100_000);
+        let stats = history.stats(3000, 100_000).unwrap();
+        assert_eq!(1, stats.readings);
+        assert_eq!(700, stats.eco2.min);
+    }
+
+    #[test]
+    fn handles_timestamp_wrap() {
+        let mut history = History::<4>::new(1000, 0, u32::MAX - 500);
+        history.add(&result(400, 10), u32::MAX - 500);
+        history.add(&result(600, 10), 600);
+        let stats = history.stats(2000, 600).unwrap();
+        assert_eq!(2, stats.readings);
+        assert_eq!(1, history.stats(1000, 600).unwrap().readings);
+    }
+
+    #[test]
+    fn skips_readings_during_warm_up() {
+        let mut history = History::<4>::new(1000, 2000, 0);
+        assert!(!history.add(&result(400, 10), 0));
+        assert!(!history.add(&result(400, 10), 1999));
+        assert!(history.add(&result(500, 10), 2000));
+        assert_eq!(2, history.skipped());
+        history.restart_warm_up(2500);
+        assert!(history.is_warming_up(2500));
+        assert!(!history.add(&result(400, 10), 3000));
+        assert_eq!(1, history.stats(4000, 3000).unwrap().readings);
+    }
+
+    #[test]
+    fn skips_readings_with_device_errors() {
+        let mut history = History::<4>::new(1000, 0, 0);
+        history.add(&result(400, 10), 0);
+        let errors = DeviceErrors {
+            heater_fault: true,
+            ..Default::default()
+        };
+        history.on_device_errors(&errors, 500);
+        assert_eq!(1, history.skipped());
+        assert_eq!(1, history.stats(4000, 500).unwrap().readings);
+    }
+
+    #[test]
+    fn can_get_percentiles() {
+        let mut history = History::<4>::new(1000, 0, 0);
+        history.add(&result(400, 40), 0);
+        history.add(&result(1000, 10), 1000);
+        history.add(&result(600, 30), 2000);
+        history.add(&result(800, 20), 3000);
+        assert_eq!(Some((400, 10)), history.percentile(4000, 0, 3000));
+        assert_eq!(Some((600, 20)), history.percentile(4000, 50, 3000));
+        assert_eq!(Some((800, 30)), history.percentile(4000, 75, 3000));
+        assert_eq!(Some((1000, 40)), history.percentile(4000, 100, 3000));
+        assert_eq!(None, history.percentile(4000, 101, 3000));
+    }
+
+    #[test]
+    fn can_clear() {
+        let mut history = History::<4>::new(1000, 0, 0);
+        history.add(&result(400, 10), 0);
+        history.clear();
+        assert_eq!(None, history.stats(4000, 0));
+    }
+}
@@ -0,0 +1,136 @@
+use crate::{AbsoluteHumidity, Celsius, RelativeHumidity};
+
+/// Magnus formula coefficient b (over water)
+const MAGNUS_B: f32 = 17.62;
+/// Magnus formula coefficient c (ºC, over water)
+const MAGNUS_C: f32 = 243.12;
+/// Saturation vapor pressure at 0ºC (hPa)
+const MAGNUS_P0: f32 = 6.112;
+/// Conversion factor from vapor pressure (hPa) over temperature (K) to
+/// absolute humidity (g/m³): molar mass of water / gas constant * 100
+const WATER_VAPOR_FACTOR: f32 = 216.74;
+/// Absolute zero (ºC)
+const ABSOLUTE_ZERO: f32 = -273.15;
+/// Tolerance (%) above 100% accepted as saturated air
+const SATURATION_TOLERANCE: f32 = 0.5;
+
+fn magnus_exponent(temperature: f32) -> f32 {
+    MAGNUS_B * temperature / (MAGNUS_C + temperature)
+}
+
+fn checked(humidity: f32) -> Option<RelativeHumidity> {
+    if (0.0..=100.0 + SATURATION_TOLERANCE).contains(&humidity) {
+        Some(RelativeHumidity(humidity.min(100.0)))
+    } else {
+        None
+    }
+}
+
+fn is_valid_temperature(temperature: f32) -> bool {
+    temperature > -MAGNUS_C && temperature.is_finite()
+}
+
+/// Compute the relative humidity from the temperature and the dew point
+/// using the Magnus formula.
+///
+/// Returns `None` if the inputs are physically impossible, for example if
+/// the dew point is above the temperature.
+pub fn relative_humidity_from_dew_point(
+    temperature: Celsius,
+    dew_point: Celsius,
+) -> Option<RelativeHumidity> {
+    if !is_valid_temperature(temperature.0) || !is_valid_temperature(dew_point.0) {
+        return None;
+    }
+    let exponent = magnus_exponent(dew_point.0) - magnus_exponent(temperature.0);
+    checked(100.0 * libm::expf(exponent))
+}
+
+/// Compute the relative humidity from the temperature and the absolute
+/// humidity using the Magnus formula.
+///
+/// Returns `None` if the inputs are physically impossible, for example if
+/// the absolute humidity is negative or above saturation.
+pub fn relative_humidity_from_absolute(
+    temperature: Celsius,
+    absolute_humidity: AbsoluteHumidity,
+) -> Option<RelativeHumidity> {
+    if !is_valid_temperature(temperature.0) || !absolute_humidity.0.is_finite() {
+        return None;
+    }
+    let saturation_pressure = MAGNUS_P0 * libm::expf(magnus_exponent(temperature.0));
+    let saturation_density =
+        WATER_VAPOR_FACTOR * saturation_pressure / (temperature.0 - ABSOLUTE_ZERO);
+    checked(100.0 * absolute_humidity.0 / saturation_density)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(expected: f32, humidity: Option<RelativeHumidity>) {
+        let humidity = humidity.unwrap().0;
+        assert!(
+            (expected - humidity).abs() < 0.1,
+            "expected {}, got {}",
+            expected,
+            humidity
+        );
+    }
+
+    #[test]
+    fn can_compute_from_dew_point() {
+        assert_close(
+            50.0,
+            relative_humidity_from_dew_point(Celsius(25.0), Celsius(13.85)),
+        );
+        assert_close(
+            100.0,
+            relative_humidity_from_dew_point(Celsius(20.0), Celsius(20.0)),
+        );
+        assert_close(
+            31.4,
+            relative_humidity_from_dew_point(Celsius(0.0), Celsius(-15.0)),
+        );
+    }
+
+    #[test]
+    fn dew_point_above_temperature_is_impossible() {
+        assert_eq!(
+            None,
+            relative_humidity_from_dew_point(Celsius(20.0), Celsius(21.0))
+        );
+    }
+
+    #[test]
+    fn can_compute_from_absolute_humidity() {
+        assert_close(
+            50.0,
+            relative_humidity_from_absolute(Celsius(25.0), AbsoluteHumidity(11.5)),
+        );
+        assert_close(
+            100.0,
+            relative_humidity_from_absolute(Celsius(20.0), AbsoluteHumidity(17.27)),
+        );
+    }
+
+    #[test]
+    fn invalid_absolute_humidity_is_impossible() {
+        assert_eq!(
+            None,
+            relative_humidity_from_absolute(Celsius(20.0), AbsoluteHumidity(-1.0))
+        );
+        assert_eq!(
+            None,
+            relative_humidity_from_absolute(Celsius(20.0), AbsoluteHumidity(20.0))
+        );
+        assert_eq!(
+            None,
+            relative_humidity_from_absolute(Celsius(-300.0), AbsoluteHumidity(1.0))
+        );
+        assert_eq!(
+            None,
+            relative_humidity_from_absolute(Celsius(f32::NAN), AbsoluteHumidity(1.0))
+        );
+    }
+}
@@ -0,0 +1,280 @@
+use crate::hal::digital::OutputPin;
+use crate::{
+    private, AlgorithmResult, DeviceErrors, Eco2Band, Eco2BandTracker, Eco2Thresholds, NoPin,
+};
+
+/// Color shown on an RGB LED.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Rgb {
+    /// Red channel on
+    pub red: bool,
+    /// Green channel on
+    pub green: bool,
+    /// Blue channel on
+    pub blue: bool,
+}
+
+impl Rgb {
+    /// All channels off
+    pub const OFF: Rgb = Rgb::new(false, false, false);
+    /// Green
+    pub const GREEN: Rgb = Rgb::new(false, true, false);
+    /// Yellow (red and green)
+    pub const YELLOW: Rgb = Rgb::new(true, true, false);
+    /// Red
+    pub const RED: Rgb = Rgb::new(true, false, false);
+    /// Blue
+    pub const BLUE: Rgb = Rgb::new(false, false, true);
+
+    /// Create a new color.
+    pub const fn new(red: bool, green: bool, blue: bool) -> Self {
+        Rgb { red, green, blue }
+    }
+}
+
+/// Configuration of an `Indicator`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct IndicatorConfig {
+    /// Color in the low band. Default: green.
+    pub low: Rgb,
+    /// Color in the medium band. Default: yellow.
+    pub medium: Rgb,
+    /// Color in the high band. Default: red.
+    pub high: Rgb,
+    /// Blinking color on sensor faults. Default: blue.
+    pub fault: Rgb,
+    /// Duration (ms) of each blink and of the gaps between blinks. Default: 250ms.
+    pub blink_ms: u32,
+    /// Sound the buzzer in the high band. Default: enabled.
+    pub buzz_on_high: bool,
+    /// Sound the buzzer along with the fault blinks. Default: disabled.
+    pub buzz_on_fault: bool,
+}
+
+impl Default for IndicatorConfig {
+    fn default() -> Self {
+        IndicatorConfig {
+            low: Rgb::GREEN,
+            medium: Rgb::YELLOW,
+            high: Rgb::RED,
+            fault: Rgb::BLUE,
+            blink_ms: 250,
+            buzz_on_high: true,
+            buzz_on_fault: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Output {
+    color: Rgb,
+    buzzer: bool,
+}
+
+/// Air quality indicator on an RGB LED and an optional buzzer.
+///
+/// The LED shows the current eCO2 band. The band follows the readings with
+/// the same hysteresis the device uses for the
+/// `InterruptMode::OnThresholdCrossed` interrupt generation, so the same
+/// thresholds passed to `set_eco2_thresholds()` can be used to update the
+/// indicator only when the interrupt is asserted. Bands computed in
+/// software can be shown with `set_band()`.
+///
+/// Sensor faults are shown by blinking the fault color a number of times
+/// followed by a pause:
+/// - 1 blink: invalid register access or invalid measurement.
+/// - 2 blinks: maximum sensor resistance.
+/// - 3 blinks: heater fault or heater supply.
+///
+/// `tick()` must be called periodically (e.g. every 50ms) to run the blink
+/// patterns. The pins are only written when their state changes.
+/// The timestamps are given in milliseconds from any monotonic clock and
+/// may wrap around.
+#[derive(Debug)]
+pub struct Indicator<R, G, B, BZ = NoPin> {
+    red: R,
+    green: G,
+    blue: B,
+    buzzer: BZ,
+    config: IndicatorConfig,
+    tracker: Eco2BandTracker,
+    band: Option<Eco2Band>,
+    fault: Option<(u32, u32)>,
+    output: Option<Output>,
+}
+
+impl<R, G, B, E> Indicator<R, G, B, NoPin>
+where
+    R: OutputPin<Error = E>,
+    G: OutputPin<Error = E>,
+    B: OutputPin<Error = E>,
+{
+    /// Create a new indicator without buzzer.
+    pub fn new(
+        red: R,
+        green: G,
+        blue: B,
+        thresholds: Eco2Thresholds,
+        config: IndicatorConfig,
+    ) -> Self {
+        Indicator {
+            red,
+            green,
+            blue,
+            buzzer: NoPin,
+            config,
+            tracker: Eco2BandTracker::new(thresholds),
+            band: None,
+            fault: None,
+            output: None,
+        }
+    }
+
+    /// Add a buzzer.
+    pub fn with_buzzer<BZ: OutputPin<Error = E>>(self, buzzer: BZ) -> Indicator<R, G, B, BZ> {
+        Indicator {
+            red: self.red,
+            green: self.green,
+            blue: self.blue,
+            buzzer,
+            config: self.config,
+            tracker: self.tracker,
+            band: self.band,
+            fault: self.fault,
+            output: self.output,
+        }
+    }
+}
+
+impl<R, G, B, BZ> Indicator<R, G, B, BZ> {
+    /// Destroy the indicator and return the pins: (red, green, blue, buzzer).
+    pub fn destroy(self) -> (R, G, B, BZ) {
+        (self.red, self.green, self.blue, self.buzzer)
+    }
+
+    /// Get the band currently shown, if any.
+    pub fn band(&self) -> Option<Eco2Band> {
+        self.band
+    }
+
+    /// Whether a sensor fault is being shown.
+    pub fn has_fault(&self) -> bool {
+        self.fault.is_some()
+    }
+}
+
+impl<R, G, B, BZ, E> Indicator<R, G, B, BZ>
+where
+    R: OutputPin<Error = E>,
+    G: OutputPin<Error = E>,
+    B: OutputPin<Error = E>,
+    BZ: private::OptionalPin<E>,
+{
+    /// Feed a new reading.
+    ///
+    /// This shows its band and clears any fault.
+    pub fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> Result<(), E> {
+        self.tracker.update(result);
+        self.band = self.tracker.band();
+        self.fault = None;
+        self.tick(now_ms)
+    }
+
+    /// Show a band computed in software.
+    ///
+    /// This clears any fault.
+    pub fn set_band(&mut self, band: Eco2Band, now_ms: u32) -> Result<(), E> {
+        self.band = Some(band);
+        self.fault = None;
+        self.tick(now_ms)
+    }
+
+    /// Feed the errors reported by the device.
+    ///
+    /// The fault is shown until the next reading or band is set.
+    pub fn on_device_errors(&mut self, errors: &DeviceErrors, now_ms: u32) -> Result<(), E> {
+        let blinks = if errors.heater_fault || errors.heater_supply {
+            3
+        } else if errors.max_resistance {
+            2
+        } else if errors.invalid_measurement
+            || errors.invalid_register_read
+            || errors.invalid_register_write
+        {
+            1
+        } else {
+            0
+        };
+        match (blinks, self.fault) {
+            (0, _) => self.fault = None,
+            (blinks, Some((current, _))) if blinks == current => (),
+            (blinks, _) => self.fault = Some((blinks, now_ms)),
+        }
+        self.tick(now_ms)
+    }
+
+    /// Update the outputs at the given time.
+    pub fn tick(&mut self, now_ms: u32) -> Result<(), E> {
+        let output = match self.fault {
+            Some((blinks, since_ms)) => {
+                let blink_ms = self.config.blink_ms.max(1);
+                let period = blink_ms.saturating_mul(2 * blinks + 4);
+                let phase = now_ms.wrapping_sub(since_ms) % period;
+                let on = phase < blink_ms.saturating_mul(2 * blinks) && (phase / blink_ms) % 2 == 0;
+                Output {
+                    color: if on { self.config.fault } else { Rgb::OFF },
+                    buzzer: on && self.config.buzz_on_fault,
+                }
+            }
+            None => match self.band {
+                Some(Eco2Band::Low) => Output {
+                    color: self.config.low,
+                    buzzer: false,
+                },
+                Some(Eco2Band::Medium) => Output {
+                    color: self.config.medium,
+                    buzzer: false,
+                },
+                Some(Eco2Band::High) => Output {
+                    color: self.config.high,
+                    buzzer: self.config.buzz_on_high,
+                },
+                None => Output {
+                    color: Rgb::OFF,
+                    buzzer: false,
+                },
+            },
+        };
+        let previous = self.output;
+        let changed = |f: fn(&Output) -> bool| match previous {
+            Some(previous) => f(&previous) != f(&output),
+            None => true,
+        };
+        if changed(|o| o.color.red) {
+            private::set_pin(&mut self.red, output.color.red)?;
+        }
+        if changed(|o| o.color.green) {
+            private::set_pin(&mut self.green, output.color.green)?;
+        }
+        if changed(|o| o.color.blue) {
+            private::set_pin(&mut self.blue, output.color.blue)?;
+        }
+        if changed(|o| o.buzzer) {
+            self.buzzer.set(output.buzzer)?;
+        }
+        self.output = Some(output);
+        Ok(())
+    }
+}
+
+impl<E> private::OptionalPin<E> for NoPin {
+    fn set(&mut self, _on: bool) -> Result<(), E> {
+        Ok(())
+    }
+}
+
+impl<P: OutputPin<Error = E>, E> private::OptionalPin<E> for P {
+    fn set(&mut self, on: bool) -> Result<(), E> {
+        private::set_pin(self, on)
+    }
+}
@@ -5,21 +5,44 @@
 //! [`embedded-hal`]: https://github.com/rust-embedded/embedded-hal
 //!
 //! This driver allows you to:
+//! - Run the complete bring-up from power-up to measuring in one call. See: [`Ccs811Builder`].
+//! - Discover the sensors present on an I²C bus. See: [`scan()`].
+//! - Operate two sensors sharing an I²C bus. See: [`Ccs811Pair`].
+//! - Monitor the readings for stuck, implausible or missing data. See: [`HealthMonitor`].
+//! - Keep a rolling history of the readings with statistics per time window. See: [`History`].
+//! - Correct the eCO2 against a reference CO2 sensor. See: [`Eco2Calibrator`] and [`Eco2Calibration`].
+//! - Smooth the readings with time-based filters. See: [`ReadingFilter`].
+//! - Detect VOC events such as cooking or occupancy. See: [`VocEventDetector`].
+//! - Switch a fan or window actuator based on the readings. See: [`VentilationController`].
+//! - Show the air quality and sensor faults on an RGB LED and a buzzer. See: [`Indicator`].
+//! - Operate sensors behind a TCA9548A I²C multiplexer. See: [`Tca9548a`] and [`MuxSensors`].
 //! - In application mode:
 //!     - Set the measurement mode. See: [`set_mode()`].
 //!     - Check if there is new data ready. See: [`has_data_ready()`].
 //!     - Get the algoritm and raw result data. See: [`data()`].
+//!     - Wait for the algorithm result data with a timeout. See: [`read_blocking()`].
+//!     - Get the algorithm result data stamped with the time it was read. See: [`data_timed()`].
 //!     - Get the raw data. See: [`raw_data()`].
 //!     - Get the current baseline. See: [`baseline()`].
 //!     - Set the baseline. See: [`set_baseline()`].
+//!     - Save and restore the baseline following the datasheet schedule. See: [`BaselineScheduler`].
 //!     - Set the environment temperature and relative humidity. See: [`set_environment()`].
+//!     - Use physical units for the readings and environment. See: [`Measurement`] and [`set_environment_typed()`].
+//!     - Compute the relative humidity from the dew point or the absolute humidity. See: [`relative_humidity_from_dew_point()`] and [`relative_humidity_from_absolute()`].
 //!     - Set the interrupt mode. See: [`set_interrupt_mode()`].
 //!     - Set the eCO2 thresholds for interrupts. See: [`set_eco2_thresholds()`].
+//!     - Apply a complete configuration and reapply it after a reset. See: [`apply()`] and [`reapply()`].
+//!     - Classify the eCO2 into bands and track band transitions. See: [`Eco2BandTracker`].
+//!     - Run a self-test and get a diagnostics report. See: [`self_test()`].
+//!     - Restart the application after an unexpected reset. See: [`restart_application()`].
+//!     - Recover automatically from unexpected resets. See: [`Ccs811Supervisor`].
+//!     - Track the measurement drive mode in the type. See: [`Ccs811Drive`].
 //! - In boot mode:
 //!     - Start application. See: [`start_application()`].
 //!     - Reset, erase, download and verify new application. See: [`update_application()`].
 //!     - Erase application. See: [`erase_application()`].
 //!     - Verify application. See: [`verify_application()`].
+//!     - Erase or verify application waiting with a timeout. See: [`erase_application_blocking()`] and [`verify_application_blocking()`].
 //!     - Download application. See: [`download_application()`].
 //! - In either mode:
 //!     - Get the firmware mode. See: [`firmware_mode()`].
@@ -28,21 +51,55 @@
 //!     - Get the hardware version. See: [`hardware_version()`].
 //!     - Get the firmware bootloader version. See: [`firmware_bootloader_version()`].
 //!     - Get the firmware application version. See: [`firmware_application_version()`].
+//!     - Identify the device and get all its information. See: [`identify()`].
 //!     - Do a software reset. See: [`software_reset()`].
-//!
+//!     - Do a hardware reset through the nRESET pin. See: [`hardware_reset()`].
+//!     - Configure when the STATUS register is checked for errors. See: [`set_status_check()`].
+//!     - Run several operations checking the STATUS register only once. See: [`batch()`].
+//!     - Run several operations waking the device only once. See: [`with_awake()`].
+//!
+//! [`Ccs811Builder`]: struct.Ccs811Builder.html
+//! [`scan()`]: fn.scan.html
+//! [`Ccs811Pair`]: struct.Ccs811Pair.html
+//! [`HealthMonitor`]: struct.HealthMonitor.html
+//! [`History`]: struct.History.html
+//! [`Eco2Calibrator`]: struct.Eco2Calibrator.html
+//! [`Eco2Calibration`]: struct.Eco2Calibration.html
+//! [`ReadingFilter`]: trait.ReadingFilter.html
+//! [`VocEventDetector`]: struct.VocEventDetector.html
+//! [`VentilationController`]: struct.VentilationController.html
+//! [`Indicator`]: struct.Indicator.html
+//! [`Tca9548a`]: struct.Tca9548a.html
+//! [`MuxSensors`]: struct.MuxSensors.html
 //! [`set_mode()`]: trait.Ccs811AppMode.html#tymethod.set_mode
 //! [`has_data_ready()`]: trait.Ccs811AppMode.html#tymethod.has_data_ready
 //! [`data()`]: trait.Ccs811AppMode.html#tymethod.data
+//! [`read_blocking()`]: trait.Ccs811AppMode.html#tymethod.read_blocking
+//! [`data_timed()`]: trait.Ccs811AppMode.html#tymethod.data_timed
 //! [`raw_data()`]: trait.Ccs811AppMode.html#tymethod.raw_data
 //! [`baseline()`]: trait.Ccs811AppMode.html#tymethod.baseline
 //! [`set_baseline()`]: trait.Ccs811AppMode.html#tymethod.set_baseline
+//! [`BaselineScheduler`]: struct.BaselineScheduler.html
 //! [`set_environment()`]: trait.Ccs811AppMode.html#tymethod.set_environment
+//! [`Measurement`]: struct.Measurement.html
+//! [`set_environment_typed()`]: trait.Ccs811AppMode.html#method.set_environment_typed
+//! [`relative_humidity_from_dew_point()`]: fn.relative_humidity_from_dew_point.html
+//! [`relative_humidity_from_absolute()`]: fn.relative_humidity_from_absolute.html
 //! [`set_interrupt_mode()`]: trait.Ccs811AppMode.html#tymethod.set_interrupt_mode
 //! [`set_eco2_thresholds()`]: trait.Ccs811AppMode.html#tymethod.set_eco2_thresholds
+//! [`apply()`]: trait.Ccs811AppMode.html#tymethod.apply
+//! [`reapply()`]: trait.Ccs811AppMode.html#tymethod.reapply
+//! [`Eco2BandTracker`]: struct.Eco2BandTracker.html
+//! [`self_test()`]: struct.Ccs811Awake.html#method.self_test
+//! [`restart_application()`]: trait.Ccs811AppMode.html#tymethod.restart_application
+//! [`Ccs811Supervisor`]: struct.Ccs811Supervisor.html
+//! [`Ccs811Drive`]: struct.Ccs811Drive.html
 //! [`start_application()`]: trait.Ccs811BootMode.html#tymethod.start_application
 //! [`update_application()`]: trait.Ccs811BootMode.html#tymethod.update_application
 //! [`erase_application()`]: trait.Ccs811BootMode.html#tymethod.erase_application
 //! [`verify_application()`]: trait.Ccs811BootMode.html#tymethod.verify_application
+//! [`erase_application_blocking()`]: trait.Ccs811BootMode.html#tymethod.erase_application_blocking
+//! [`verify_application_blocking()`]: trait.Ccs811BootMode.html#tymethod.verify_application_blocking
 //! [`download_application()`]: trait.Ccs811BootMode.html#tymethod.download_application
 //! [`firmware_mode()`]: trait.Ccs811Device.html#tymethod.firmware_mode
 //! [`has_valid_app()`]: trait.Ccs811Device.html#tymethod.has_valid_app
@@ -50,7 +107,12 @@
 //! [`hardware_version()`]: trait.Ccs811Device.html#tymethod.hardware_version
 //! [`firmware_bootloader_version()`]: trait.Ccs811Device.html#tymethod.firmware_bootloader_version
 //! [`firmware_application_version()`]: trait.Ccs811Device.html#tymethod.firmware_application_version
+//! [`identify()`]: trait.Ccs811Device.html#tymethod.identify
 //! [`software_reset()`]: trait.Ccs811Device.html#tymethod.software_reset
+//! [`hardware_reset()`]: struct.Ccs811.html#method.hardware_reset
+//! [`set_status_check()`]: struct.Ccs811Awake.html#method.set_status_check
+//! [`batch()`]: struct.Ccs811Awake.html#method.batch
+//! [`with_awake()`]: struct.Ccs811.html#method.with_awake
 //!
 //! [Introductory blog post](https://blog.eldruin.com/ccs811-indoor-air-quality-sensor-driver-in-rust)
 //!
@@ -82,6 +144,17 @@
 //! - [Datasheet](https://www.sciosense.com/wp-content/uploads/2020/01/CCS811-Datasheet.pdf)
 //! - [Programming and interfacing guide](https://www.sciosense.com/wp-content/uploads/2020/01/CCS811-Application-Note-Programming-and-interfacing-guide.pdf)
 //!
+//! ## Optional features
+//!
+//! - `std`: `StdClock` based on `std::time::Instant`.
+//! - `embassy-time`: `EmbassyClock` based on `embassy_time::Instant`.
+//! - `uom`: Conversions of the unit types into [`uom`] quantities.
+//! - `libm`: Relative humidity from the dew point or the absolute humidity
+//!   based on [`libm`].
+//!
+//! [`uom`]: https://crates.io/crates/uom
+//! [`libm`]: https://crates.io/crates/libm
+//!
 //! ## Usage examples (see also examples folder)
 //!
 //! To use this driver, import this crate and an `embedded_hal` implementation,
@@ -125,6 +198,90 @@
 //! }
 //! ```
 //!
+//! ### Initialize the device in one call
+//!
+//! The device is reset, identified, its application started and configured.
+//!
+//! ```no_run
+//! use linux_embedded_hal::{I2cdev, Delay};
+//! use embedded_ccs811::{prelude::*, Ccs811Builder, SlaveAddr, MeasurementMode};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut sensor = Ccs811Builder::new(dev, SlaveAddr::default())
+//!     .mode(MeasurementMode::ConstantPower1s)
+//!     .environment(50.0, 25.0)
+//!     .build(&mut Delay {})
+//!     .map_err(|e| e.step)
+//!     .unwrap();
+//! // Give up if there is no new data after 5 seconds.
+//! let data = sensor.read_blocking(&mut Delay {}, 5_000).unwrap();
+//! println!("eCO2: {}, eTVOC: {}", data.eco2, data.etvoc);
+//! ```
+//!
+//! ### Discover the sensors on the bus
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use embedded_ccs811::{prelude::*, scan, DiscoveredDevice};
+//!
+//! let mut dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! for sensor in scan(&mut dev).unwrap().iter().flatten() {
+//!     println!("Found sensor at {:?} in {:?} mode", sensor.address, sensor.firmware_mode);
+//!     if let DiscoveredDevice::Boot(sensor) = sensor.into_device(&mut dev) {
+//!         let _sensor = sensor.start_application().ok().unwrap();
+//!     }
+//! }
+//! ```
+//!
+//! ### Operate two sensors sharing the bus
+//!
+//! Each sensor is woken up through its own nWAKE pin.
+//!
+//! ```no_run
+//! use core::cell::RefCell;
+//! use embedded_hal_bus::i2c::RefCellDevice;
+//! use linux_embedded_hal::{I2cdev, CdevPin, Delay};
+//! use linux_embedded_hal::gpio_cdev::{Chip, LineRequestFlags};
+//! use embedded_ccs811::{Ccs811, Ccs811Pair, SlaveAddr, MeasurementMode};
+//! use nb::block;
+//!
+//! let bus = RefCell::new(I2cdev::new("/dev/i2c-1").unwrap());
+//! let mut chip = Chip::new("/dev/gpiochip0").unwrap();
+//! let handle = chip.get_line(17).unwrap()
+//!   .request(LineRequestFlags::OUTPUT, 0, "output").unwrap();
+//! let nwake1 = CdevPin::new(handle).unwrap();
+//! let handle = chip.get_line(27).unwrap()
+//!   .request(LineRequestFlags::OUTPUT, 0, "output").unwrap();
+//! let nwake2 = CdevPin::new(handle).unwrap();
+//! let first = Ccs811::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(false), nwake1, Delay {});
+//! let second = Ccs811::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(true), nwake2, Delay {});
+//! let mut pair = Ccs811Pair::new(first, second).start_application().ok().unwrap();
+//! pair.set_mode(MeasurementMode::ConstantPower1s).unwrap();
+//! loop {
+//!     let (data1, data2) = block!(pair.data()).unwrap();
+//!     println!("eCO2: {}, {}", data1.eco2, data2.eco2);
+//! }
+//! ```
+//!
+//! ### Operate sensors behind a TCA9548A multiplexer
+//!
+//! ```no_run
+//! use linux_embedded_hal::I2cdev;
+//! use embedded_ccs811::{prelude::*, MuxSensors, SlaveAddr, Tca9548a, MeasurementMode};
+//!
+//! let mux = Tca9548a::new(I2cdev::new("/dev/i2c-1").unwrap(), 0x70);
+//! let sensors = MuxSensors::new(&mux, 0b1111_1111, SlaveAddr::default());
+//! let (mut sensors, _errors) = sensors.start_application();
+//! sensors.map(|sensor| sensor.set_mode(MeasurementMode::ConstantPower1s));
+//! loop {
+//!     for (channel, data) in sensors.data().iter().enumerate() {
+//!         if let Some(Ok(data)) = data {
+//!             println!("Channel {}: eCO2: {}", channel, data.eco2);
+//!         }
+//!     }
+//! }
+//! ```
+//!
 //! ### Save and restore the baseline
 //!
 //! ```no_run
@@ -172,6 +329,27 @@
 //! sensor.set_mode(MeasurementMode::ConstantPower1s).unwrap();
 //! ```
 //!
+//! ### Configure the device in a single wake cycle
+//!
+//! ```no_run
+//! use linux_embedded_hal::{I2cdev, CdevPin, Delay};
+//! use linux_embedded_hal::gpio_cdev::{Chip, LineRequestFlags};
+//! use embedded_ccs811::{prelude::*, Ccs811, SlaveAddr, InterruptMode, MeasurementMode};
+//!
+//! let dev = I2cdev::new("/dev/i2c-1").unwrap();
+//! let mut chip = Chip::new("/dev/gpiochip0").unwrap();
+//! let handle = chip.get_line(17).unwrap()
+//!   .request(LineRequestFlags::OUTPUT, 0, "output").unwrap();
+//! let nwake = CdevPin::new(handle).unwrap();
+//! let sensor = Ccs811::new(dev, SlaveAddr::default(), nwake, Delay {});
+//! let mut sensor = sensor.start_application().ok().unwrap();
+//! sensor.with_awake(|dev| {
+//!     dev.set_mode(MeasurementMode::ConstantPower1s)?;
+//!     dev.set_interrupt_mode(InterruptMode::OnDataReady)?;
+//!     dev.set_environment(50.0, 25.0)
+//! }).unwrap();
+//! ```
+//!
 //! ### Get hardware and firmware information
 //!
 //! ```no_run
@@ -195,6 +373,8 @@
 #![no_std]
 
 extern crate embedded_hal as hal;
+#[cfg(feature = "std")]
+extern crate std;
 use core::marker::PhantomData;
 
 mod common_impl;
@@ -203,23 +383,81 @@ mod register_access;
 use crate::register_access::{BitFlags, Register};
 mod app_mode;
 mod boot_mode;
+mod builder;
+pub use crate::builder::{Ccs811Builder, InitDevice, InitError, InitStep};
+mod discovery;
+pub use crate::discovery::{scan, DiscoveredDevice, DiscoveredSensor};
+mod pair;
+pub use crate::pair::{Ccs811Pair, PairError};
+mod diagnostics;
+pub use crate::diagnostics::DiagnosticReport;
+mod blocking;
+mod clock;
+pub use crate::clock::Clock;
+#[cfg(feature = "embassy-time")]
+pub use crate::clock::EmbassyClock;
+#[cfg(feature = "std")]
+pub use crate::clock::StdClock;
+mod drive_mode;
+pub use crate::drive_mode::{AlgorithmDriveMode, Ccs811Drive, DriveMode};
+mod thresholds;
+pub use crate::thresholds::{
+    Eco2Band, Eco2BandTracker, Eco2BandTransition, Eco2Thresholds, ECO2_HYSTERESIS,
+};
+mod health;
+pub use crate::health::{HealthMonitor, HealthPolicy, HealthStatus};
+mod events;
+pub use crate::events::{VocEvent, VocEventConfig, VocEventDetector};
+mod filter;
+pub use crate::filter::{EmaFilter, MedianFilter, ReadingFilter, SpikeFilter};
+mod indicator;
+pub use crate::indicator::{Indicator, IndicatorConfig, Rgb};
+mod ventilation;
+pub use crate::ventilation::{
+    FanOutput, Hysteresis, PwmFan, VentilationConfig, VentilationController, VentilationOverride,
+};
+mod history;
+pub use crate::history::{History, HistoryStats, Statistics};
+mod calibration;
+pub use crate::calibration::{
+    Eco2Calibration, Eco2Calibrator, CALIBRATION_BYTES, MAX_CALIBRATION_KNOTS,
+};
+mod supervisor;
+pub use crate::supervisor::{Ccs811Supervisor, SupervisorError};
+mod baseline;
+pub use crate::baseline::{BaselineEvent, BaselineScheduleConfig, BaselineScheduler};
+mod mux;
+pub use crate::mux::{MuxChannel, MuxSensors, Tca9548a, TCA9548A_CHANNELS};
 mod traits;
 pub use crate::traits::{Ccs811AppMode, Ccs811BootMode, Ccs811Device};
+mod units;
+pub use crate::units::{
+    AbsoluteHumidity, Celsius, Measurement, MicroAmps, Ppb, Ppm, RelativeHumidity, Volts,
+};
+#[cfg(feature = "libm")]
+mod humidity;
+#[cfg(feature = "libm")]
+pub use crate::humidity::{relative_humidity_from_absolute, relative_humidity_from_dew_point};
 mod types;
 pub use crate::types::{
-    AlgorithmResult, DeviceErrors, Error, ErrorAwake, FirmwareMode, InterruptMode, MeasurementMode,
-    ModeChangeError, SlaveAddr,
+    AlgorithmResult, CalibratedResult, Ccs811Config, DeviceErrors, DeviceInfo, Error, ErrorAwake,
+    FirmwareMode, InterruptMode, MeasurementMode, ModeChangeError, NoPin, SlaveAddr, StatusCheck,
+    TimedResult,
 };
 pub use nb;
 
 /// CCS811 device driver
 ///
 /// Convenience wrapper arount `Ccs811Awake` which handles waking up the device on each operation.
+///
+/// Optionally, the nRESET pin can be provided as well to reset the device
+/// through it. See `Ccs811::new_with_reset_pin()`.
 #[derive(Debug)]
-pub struct Ccs811<I2C, NWAKE, WAKEDELAY, MODE> {
+pub struct Ccs811<I2C, NWAKE, WAKEDELAY, MODE, NRESET = NoPin> {
     dev: Ccs811Awake<I2C, MODE>,
     n_wake_pin: NWAKE,
     wake_delay: WAKEDELAY,
+    n_reset_pin: NRESET,
     _mode: PhantomData<MODE>,
 }
 
@@ -232,8 +470,12 @@ pub struct Ccs811Awake<I2C, MODE> {
     /// The concrete I²C device implementation.
     i2c: I2C,
     address: u8,
-    meas_mode_reg: u8,
+    /// MEAS_MODE register value. `None` if unknown since the application started.
+    meas_mode_reg: Option<u8>,
     in_progress: ActionInProgress,
+    status_check: StatusCheck,
+    config: Option<Ccs811Config>,
+    last_sample_ms: Option<u32>,
     _mode: PhantomData<MODE>,
 }
 
@@ -247,17 +489,92 @@ enum ActionInProgress {
 /// Mode marker
 pub mod mode {
     /// Boot mode
+    #[derive(Debug)]
     pub struct Boot(());
     /// App mode
+    #[derive(Debug)]
     pub struct App(());
 }
 
+/// Measurement drive mode marker. See `Ccs811Drive`.
+pub mod drive {
+    /// Idle. Measurements are disabled. (Mode 0)
+    #[derive(Debug)]
+    pub struct Idle(());
+    /// Constant power mode. IAQ measurement every second. (Mode 1)
+    #[derive(Debug)]
+    pub struct ConstantPower1s(());
+    /// Pulse heating mode. IAQ measurement every 10 seconds. (Mode 2)
+    #[derive(Debug)]
+    pub struct PulseHeating10s(());
+    /// Low power pulse heating mode. IAQ measurement every 60 seconds. (Mode 3)
+    #[derive(Debug)]
+    pub struct LowPowerPulseHeating60s(());
+    /// Constant power mode. Raw data measurement every 250ms. (Mode 4)
+    #[derive(Debug)]
+    pub struct ConstantPower250ms(());
+}
+
 mod private {
-    use super::{mode, Ccs811, Ccs811Awake};
+    use super::{drive, hal::digital::OutputPin, mode, Ccs811, Ccs811Awake, Error, ErrorAwake};
     pub trait Sealed {}
 
+    impl Sealed for drive::Idle {}
+    impl Sealed for drive::ConstantPower1s {}
+    impl Sealed for drive::PulseHeating10s {}
+    impl Sealed for drive::LowPowerPulseHeating60s {}
+    impl Sealed for drive::ConstantPower250ms {}
+
+    pub trait UnexpectedReset {
+        fn is_unexpected_reset(&self) -> bool;
+    }
+
+    impl<E> UnexpectedReset for ErrorAwake<E> {
+        fn is_unexpected_reset(&self) -> bool {
+            matches!(self, ErrorAwake::UnexpectedReset)
+        }
+    }
+
+    impl<CommE, PinE> UnexpectedReset for Error<CommE, PinE> {
+        fn is_unexpected_reset(&self) -> bool {
+            matches!(self, Error::UnexpectedReset)
+        }
+    }
+
+    pub trait ModeMarker {
+        const IS_APP: bool;
+    }
+
+    impl ModeMarker for mode::Boot {
+        const IS_APP: bool = false;
+    }
+
+    impl ModeMarker for mode::App {
+        const IS_APP: bool = true;
+    }
+
+    /// Initialization of a `Ccs811Builder` without an nINT pin.
+    pub trait Build<D> {
+        type Output;
+        type Error;
+        fn build(self, delay: &mut D) -> Result<Self::Output, Self::Error>;
+    }
+
+    pub trait OptionalPin<E> {
+        fn set(&mut self, on: bool) -> Result<(), E>;
+    }
+
+    /// Drive an output pin high when on and low otherwise.
+    pub fn set_pin<P: OutputPin>(pin: &mut P, on: bool) -> Result<(), P::Error> {
+        if on {
+            pin.set_high()
+        } else {
+            pin.set_low()
+        }
+    }
+
     impl Sealed for mode::Boot {}
     impl Sealed for mode::App {}
-    impl<I2C, NWAKE, WAKEDELAY, MODE> Sealed for Ccs811<I2C, NWAKE, WAKEDELAY, MODE> {}
+    impl<I2C, NWAKE, WAKEDELAY, MODE, NRESET> Sealed for Ccs811<I2C, NWAKE, WAKEDELAY, MODE, NRESET> {}
     impl<I2C, MODE> Sealed for Ccs811Awake<I2C, MODE> {}
 }
@@ -0,0 +1,204 @@
+use crate::hal::i2c::{ErrorType, I2c, Operation};
+use crate::{
+    mode, AlgorithmResult, Ccs811AppMode, Ccs811Awake, Ccs811BootMode, ErrorAwake, ModeChangeError,
+    SlaveAddr,
+};
+use core::cell::RefCell;
+
+/// Number of channels of a TCA9548A multiplexer.
+pub const TCA9548A_CHANNELS: usize = 8;
+
+/// TCA9548A I²C multiplexer.
+///
+/// Devices behind the multiplexer are accessed through a `MuxChannel`, which
+/// selects its channel before each transaction. This allows operating several
+/// CCS811 devices at the same slave address.
+#[derive(Debug)]
+pub struct Tca9548a<I2C> {
+    i2c: RefCell<I2C>,
+    address: u8,
+}
+
+impl<I2C> Tca9548a<I2C> {
+    /// Create a new multiplexer at the given slave address (0x70 to 0x77).
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Tca9548a {
+            i2c: RefCell::new(i2c),
+            address,
+        }
+    }
+
+    /// Destroy the multiplexer instance, return I²C bus instance.
+    pub fn destroy(self) -> I2C {
+        self.i2c.into_inner()
+    }
+
+    /// Get an I²C bus for a channel (0 to 7).
+    ///
+    /// Returns `None` if the channel does not exist.
+    pub fn channel(&self, channel: u8) -> Option<MuxChannel<'_, I2C>> {
+        if usize::from(channel) < TCA9548A_CHANNELS {
+            Some(MuxChannel { mux: self, channel })
+        } else {
+            None
+        }
+    }
+}
+
+/// I²C bus behind one channel of a `Tca9548a` multiplexer.
+#[derive(Debug)]
+pub struct MuxChannel<'a, I2C> {
+    mux: &'a Tca9548a<I2C>,
+    channel: u8,
+}
+
+impl<'a, I2C> MuxChannel<'a, I2C> {
+    /// Get the channel number.
+    pub fn channel(&self) -> u8 {
+        self.channel
+    }
+}
+
+impl<'a, I2C: ErrorType> ErrorType for MuxChannel<'a, I2C> {
+    type Error = I2C::Error;
+}
+
+impl<'a, I2C: I2c> MuxChannel<'a, I2C> {
+    fn on_channel<T, F>(&mut self, f: F) -> Result<T, I2C::Error>
+    where
+        F: FnOnce(&mut I2C) -> Result<T, I2C::Error>,
+    {
+        let mut i2c = self.mux.i2c.borrow_mut();
+        i2c.write(self.mux.address, &[1 << self.channel])?;
+        f(&mut i2c)
+    }
+}
+
+impl<'a, I2C: I2c> I2c for MuxChannel<'a, I2C> {
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.on_channel(|i2c| i2c.read(address, read))
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.on_channel(|i2c| i2c.write(address, write))
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.on_channel(|i2c| i2c.write_read(address, write, read))
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.on_channel(|i2c| i2c.transaction(address, operations))
+    }
+}
+
+/// CCS811 devices behind the channels of a `Tca9548a` multiplexer.
+///
+/// There can be one device per channel, all at the same slave address.
+#[derive(Debug)]
+pub struct MuxSensors<'a, I2C, MODE> {
+    sensors: [Option<Ccs811Awake<MuxChannel<'a, I2C>, MODE>>; TCA9548A_CHANNELS],
+}
+
+impl<'a, I2C, MODE> MuxSensors<'a, I2C, MODE> {
+    /// Get the device on a channel.
+    pub fn sensor(&mut self, channel: u8) -> Option<&mut Ccs811Awake<MuxChannel<'a, I2C>, MODE>> {
+        self.sensors
+            .get_mut(usize::from(channel))
+            .and_then(Option::as_mut)
+    }
+
+    /// Run an operation on each device.
+    ///
+    /// The result is in channel order. Channels without a device are `None`.
+    pub fn map<T, F>(&mut self, mut f: F) -> [Option<T>; TCA9548A_CHANNELS]
+    where
+        F: FnMut(&mut Ccs811Awake<MuxChannel<'a, I2C>, MODE>) -> T,
+    {
+        let mut results: [Option<T>; TCA9548A_CHANNELS] = Default::default();
+        for (result, sensor) in results.iter_mut().zip(self.sensors.iter_mut()) {
+            if let Some(sensor) = sensor {
+                *result = Some(f(sensor));
+            }
+        }
+        results
+    }
+
+    /// Destroy the devices, releasing the multiplexer.
+    ///
+    /// Returns the bitmask of the channels which had a device. See `new()`.
+    pub fn destroy(self) -> u8 {
+        self.sensors
+            .iter()
+            .enumerate()
+            .filter(|(_, sensor)| sensor.is_some())
+            .fold(0, |channels, (channel, _)| channels | (1 << channel))
+    }
+}
+
+impl<'a, I2C: I2c> MuxSensors<'a, I2C, mode::Boot> {
+    /// Create the devices on the channels selected by the bitmask.
+    ///
+    /// For example, `0b0000_0101` creates devices on channels 0 and 2.
+    pub fn new(mux: &'a Tca9548a<I2C>, channels: u8, address: SlaveAddr) -> Self {
+        let mut sensors: [Option<_>; TCA9548A_CHANNELS] = Default::default();
+        for (channel, sensor) in sensors.iter_mut().enumerate() {
+            if channels & (1 << channel) != 0 {
+                let i2c = MuxChannel {
+                    mux,
+                    channel: channel as u8,
+                };
+                *sensor = Some(Ccs811Awake::new(i2c, address));
+            }
+        }
+        MuxSensors { sensors }
+    }
+
+    /// Start the application on all devices.
+    ///
+    /// The devices which started are returned together with the errors of
+    /// the devices which did not, in channel order.
+    #[allow(clippy::type_complexity)]
+    pub fn start_application(
+        self,
+    ) -> (
+        MuxSensors<'a, I2C, mode::App>,
+        [Option<
+            ModeChangeError<ErrorAwake<I2C::Error>, Ccs811Awake<MuxChannel<'a, I2C>, mode::Boot>>,
+        >; TCA9548A_CHANNELS],
+    ) {
+        let mut started: [Option<_>; TCA9548A_CHANNELS] = Default::default();
+        let mut errors: [Option<_>; TCA9548A_CHANNELS] = Default::default();
+        let devices = started.iter_mut().zip(errors.iter_mut());
+        for ((started, error), sensor) in devices.zip(self.sensors) {
+            if let Some(sensor) = sensor {
+                match sensor.start_application() {
+                    Ok(sensor) => *started = Some(sensor),
+                    Err(e) => *error = Some(e),
+                }
+            }
+        }
+        (MuxSensors { sensors: started }, errors)
+    }
+}
+
+impl<'a, I2C: I2c> MuxSensors<'a, I2C, mode::App> {
+    /// Get the algorithm results of all devices, in channel order.
+    ///
+    /// Channels without a device are `None`.
+    #[allow(clippy::type_complexity)]
+    pub fn data(
+        &mut self,
+    ) -> [Option<nb::Result<AlgorithmResult, ErrorAwake<I2C::Error>>>; TCA9548A_CHANNELS] {
+        self.map(|sensor| sensor.data())
+    }
+}
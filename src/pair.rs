@@ -0,0 +1,182 @@
+use crate::{AlgorithmResult, Ccs811AppMode, Ccs811BootMode, InterruptMode, MeasurementMode};
+
+/// Two CCS811 devices sharing an I²C bus.
+///
+/// The two devices must be configured at different slave addresses
+/// (0x5A and 0x5B) and get each their own handle to the shared bus, for example
+/// an `embedded-hal-bus` `RefCellDevice` or `CriticalSectionDevice`.
+/// If using `Ccs811`, each device is woken up independently through its own
+/// nWAKE pin.
+///
+/// Operations are always run on both devices and the errors are reported per device.
+#[derive(Debug)]
+pub struct Ccs811Pair<A, B> {
+    first: A,
+    second: B,
+    first_data: Option<AlgorithmResult>,
+    second_data: Option<AlgorithmResult>,
+}
+
+/// Errors reported by the devices in a `Ccs811Pair`.
+#[derive(Debug)]
+pub enum PairError<EA, EB> {
+    /// The first device reported an error.
+    First(EA),
+    /// The second device reported an error.
+    Second(EB),
+    /// Both devices reported an error.
+    Both(EA, EB),
+}
+
+impl<EA, EB> PairError<EA, EB> {
+    fn from_results<TA, TB>(first: Result<TA, EA>, second: Result<TB, EB>) -> Result<(), Self> {
+        match (first, second) {
+            (Ok(_), Ok(_)) => Ok(()),
+            (Err(a), Ok(_)) => Err(PairError::First(a)),
+            (Ok(_), Err(b)) => Err(PairError::Second(b)),
+            (Err(a), Err(b)) => Err(PairError::Both(a, b)),
+        }
+    }
+}
+
+impl<A, B> Ccs811Pair<A, B> {
+    /// Create a new pair of devices.
+    pub fn new(first: A, second: B) -> Self {
+        Ccs811Pair {
+            first,
+            second,
+            first_data: None,
+            second_data: None,
+        }
+    }
+
+    /// Destroy the pair, returning both devices.
+    pub fn destroy(self) -> (A, B) {
+        (self.first, self.second)
+    }
+
+    /// Get a mutable reference to the first device.
+    pub fn first(&mut self) -> &mut A {
+        &mut self.first
+    }
+
+    /// Get a mutable reference to the second device.
+    pub fn second(&mut self) -> &mut B {
+        &mut self.second
+    }
+}
+
+impl<A, B> Ccs811Pair<A, B>
+where
+    A: Ccs811BootMode,
+    B: Ccs811BootMode,
+{
+    /// Start the application on both devices.
+    ///
+    /// If any of the devices fails to start, the individual results are
+    /// returned so that each device can be recovered.
+    #[allow(clippy::type_complexity)]
+    pub fn start_application(
+        self,
+    ) -> Result<
+        Ccs811Pair<A::TargetType, B::TargetType>,
+        (
+            Result<A::TargetType, A::ModeChangeError>,
+            Result<B::TargetType, B::ModeChangeError>,
+        ),
+    > {
+        match (
+            self.first.start_application(),
+            self.second.start_application(),
+        ) {
+            (Ok(first), Ok(second)) => Ok(Ccs811Pair::new(first, second)),
+            results => Err(results),
+        }
+    }
+}
+
+impl<A, B> Ccs811Pair<A, B>
+where
+    A: Ccs811AppMode,
+    B: Ccs811AppMode,
+{
+    /// Set the measurement mode on both devices.
+    pub fn set_mode(&mut self, mode: MeasurementMode) -> Result<(), PairError<A::Error, B::Error>> {
+        PairError::from_results(self.first.set_mode(mode), self.second.set_mode(mode))
+    }
+
+    /// Set the environment temperature and relative humidity on both devices.
+    pub fn set_environment(
+        &mut self,
+        humidity_percentage: f32,
+        temperature_celsius: f32,
+    ) -> Result<(), PairError<A::Error, B::Error>> {
+        PairError::from_results(
+            self.first
+                .set_environment(humidity_percentage, temperature_celsius),
+            self.second
+                .set_environment(humidity_percentage, temperature_celsius),
+        )
+    }
+
+    /// Configure the interrupt generation on both devices.
+    pub fn set_interrupt_mode(
+        &mut self,
+        mode: InterruptMode,
+    ) -> Result<(), PairError<A::Error, B::Error>> {
+        PairError::from_results(
+            self.first.set_interrupt_mode(mode),
+            self.second.set_interrupt_mode(mode),
+        )
+    }
+
+    /// Set the eCO2 threshold values for interrupt generation on both devices.
+    pub fn set_eco2_thresholds(
+        &mut self,
+        low_to_medium: u16,
+        medium_to_high: u16,
+    ) -> Result<(), PairError<A::Error, B::Error>> {
+        PairError::from_results(
+            self.first
+                .set_eco2_thresholds(low_to_medium, medium_to_high),
+            self.second
+                .set_eco2_thresholds(low_to_medium, medium_to_high),
+        )
+    }
+
+    /// Get the algorithm results of both devices: (first, second).
+    ///
+    /// Each call polls the devices which do not have new data yet. The
+    /// data of a device is kept until the other device has new data as well,
+    /// also if the other device reports an error.
+    #[allow(clippy::type_complexity)]
+    pub fn data(
+        &mut self,
+    ) -> nb::Result<(AlgorithmResult, AlgorithmResult), PairError<A::Error, B::Error>> {
+        let first = poll(&mut self.first, &mut self.first_data);
+        let second = poll(&mut self.second, &mut self.second_data);
+        PairError::from_results(first, second).map_err(nb::Error::Other)?;
+        match (self.first_data, self.second_data) {
+            (Some(first), Some(second)) => {
+                self.first_data = None;
+                self.second_data = None;
+                Ok((first, second))
+            }
+            _ => Err(nb::Error::WouldBlock),
+        }
+    }
+}
+
+fn poll<DEV: Ccs811AppMode>(
+    dev: &mut DEV,
+    data: &mut Option<AlgorithmResult>,
+) -> Result<(), DEV::Error> {
+    if data.is_none() {
+        match dev.data() {
+            Ok(result) => *data = Some(result),
+            Err(nb::Error::WouldBlock) => (),
+            Err(nb::Error::Other(e)) => return Err(e),
+        }
+    }
+    Ok(())
+}
@@ -1,4 +1,4 @@
-use crate::{hal, Ccs811Awake, DeviceErrors, ErrorAwake};
+use crate::{hal, Ccs811Awake, DeviceErrors, ErrorAwake, StatusCheck};
 
 pub(crate) struct Register {}
 impl Register {
@@ -21,6 +21,11 @@ impl Register {
     pub const SW_RESET: u8 = 0xFF;
 }
 
+/// Expected hardware ID of the CCS81x family of devices.
+pub(crate) const HARDWARE_ID: u8 = 0x81;
+/// Expected hardware major version of the CCS81x family of devices.
+pub(crate) const HARDWARE_VERSION_MAJOR: u8 = 1;
+
 pub(crate) struct BitFlags {}
 impl BitFlags {
     pub const DATA_READY: u8 = 1 << 3;
@@ -44,16 +49,24 @@ where
     I2C: hal::i2c::I2c<Error = E>,
 {
     pub(crate) fn check_status_error(&mut self) -> Result<(), ErrorAwake<E>> {
-        self.read_status().map(drop)
+        match self.status_check {
+            StatusCheck::Always => self.read_status().map(drop),
+            StatusCheck::Deferred | StatusCheck::Never => Ok(()),
+        }
     }
 
     pub(crate) fn read_status(&mut self) -> Result<u8, ErrorAwake<E>> {
-        let mut data = [0];
-        self.i2c
-            .write_read(self.address, &[Register::STATUS], &mut data)
-            .map_err(ErrorAwake::I2C)?;
-        let status = data[0];
+        if self.status_check == StatusCheck::Never {
+            self.read_status_only()
+        } else {
+            self.read_status_with_errors()
+        }
+    }
+
+    pub(crate) fn read_status_with_errors(&mut self) -> Result<u8, ErrorAwake<E>> {
+        let status = self.read_status_only()?;
         if (status & BitFlags::ERROR) != 0 {
+            let mut data = [0];
             self.i2c
                 .write_read(self.address, &[Register::ERROR_ID], &mut data)
                 .map_err(ErrorAwake::I2C)?;
@@ -62,6 +75,14 @@ where
         Ok(status)
     }
 
+    pub(crate) fn read_status_only(&mut self) -> Result<u8, ErrorAwake<E>> {
+        let mut data = [0];
+        self.i2c
+            .write_read(self.address, &[Register::STATUS], &mut data)
+            .map_err(ErrorAwake::I2C)?;
+        Ok(data[0])
+    }
+
     pub(crate) fn read_register_1byte(&mut self, register: u8) -> Result<u8, ErrorAwake<E>> {
         let mut data = [0];
         self.read_register(register, &mut data).and(Ok(data[0]))
@@ -102,6 +123,15 @@ where
     }
 }
 
+/// Check that the device is still in application mode.
+pub(crate) fn check_app_mode<E>(status: u8) -> Result<(), ErrorAwake<E>> {
+    if (status & BitFlags::FW_MODE) == 0 {
+        Err(ErrorAwake::UnexpectedReset)
+    } else {
+        Ok(())
+    }
+}
+
 pub(crate) fn get_errors(error_id: u8) -> Result<(), DeviceErrors> {
     let mut has_error = false;
     let mut errors = DeviceErrors::default();
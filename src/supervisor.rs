@@ -0,0 +1,174 @@
+use crate::hal::delay::DelayNs;
+use crate::private::UnexpectedReset;
+use crate::{AlgorithmResult, Ccs811AppMode, Ccs811Config, InterruptMode, MeasurementMode};
+
+/// Supervisor recovering a device in application mode from unexpected resets.
+///
+/// A brown-out can put the device back into boot mode. The supervisor
+/// detects this through the `UnexpectedReset` error, restarts the application
+/// and restores the configuration recorded by the driver with
+/// `Ccs811AppMode::reapply()`. The record is created by `apply()` or by the
+/// first setter, either through the supervisor or directly on the device.
+/// If the operation which detected the reset was a setter, it is done again
+/// after the recovery.
+#[derive(Debug)]
+pub struct Ccs811Supervisor<DEV, D> {
+    dev: DEV,
+    delay: D,
+    recoveries: u32,
+}
+
+/// Errors reported by `Ccs811Supervisor`.
+#[derive(Debug)]
+pub enum SupervisorError<E> {
+    /// Error reported by the device.
+    Error(E),
+    /// The device was unexpectedly reset. The application was restarted and
+    /// the configuration restored.
+    Recovered,
+    /// The device was unexpectedly reset and the recovery failed.
+    ///
+    /// Contains the error occurred during the recovery.
+    RecoveryFailed(E),
+    /// The device was unexpectedly reset and the application was restarted,
+    /// but no configuration was recorded so the device stays in `Idle` mode.
+    Unconfigured,
+}
+
+impl<DEV, D> Ccs811Supervisor<DEV, D> {
+    /// Create a new supervisor for a device in application mode.
+    ///
+    /// The delay is used to wait for the application to start on recovery.
+    pub fn new(dev: DEV, delay: D) -> Self {
+        Ccs811Supervisor {
+            dev,
+            delay,
+            recoveries: 0,
+        }
+    }
+
+    /// Destroy the supervisor, return the device and delay instances.
+    pub fn destroy(self) -> (DEV, D) {
+        (self.dev, self.delay)
+    }
+
+    /// Get the supervised device.
+    pub fn device(&mut self) -> &mut DEV {
+        &mut self.dev
+    }
+
+    /// Get the number of recoveries done.
+    pub fn recoveries(&self) -> u32 {
+        self.recoveries
+    }
+}
+
+impl<DEV, D, E> Ccs811Supervisor<DEV, D>
+where
+    DEV: Ccs811AppMode<Error = E>,
+    E: UnexpectedReset,
+    D: DelayNs,
+{
+    /// Apply a complete configuration. See `Ccs811AppMode::apply()`.
+    pub fn apply(&mut self, config: &Ccs811Config) -> Result<(), SupervisorError<E>> {
+        let result = self.dev.apply(config);
+        self.supervise(result, |dev| dev.apply(config))
+    }
+
+    /// Set the measurement mode. See `Ccs811AppMode::set_mode()`.
+    pub fn set_mode(&mut self, mode: MeasurementMode) -> Result<(), SupervisorError<E>> {
+        let result = self.dev.set_mode(mode);
+        self.supervise(result, |dev| dev.set_mode(mode))
+    }
+
+    /// Configure the interrupt generation. See `Ccs811AppMode::set_interrupt_mode()`.
+    pub fn set_interrupt_mode(&mut self, mode: InterruptMode) -> Result<(), SupervisorError<E>> {
+        let result = self.dev.set_interrupt_mode(mode);
+        self.supervise(result, |dev| dev.set_interrupt_mode(mode))
+    }
+
+    /// Set the eCO2 threshold values for interrupt generation.
+    ///
+    /// See `Ccs811AppMode::set_eco2_thresholds()`.
+    pub fn set_eco2_thresholds(
+        &mut self,
+        low_to_medium: u16,
+        medium_to_high: u16,
+    ) -> Result<(), SupervisorError<E>> {
+        let result = self.dev.set_eco2_thresholds(low_to_medium, medium_to_high);
+        self.supervise(result, |dev| {
+            dev.set_eco2_thresholds(low_to_medium, medium_to_high)
+        })
+    }
+
+    /// Set the environment temperature and relative humidity.
+    ///
+    /// See `Ccs811AppMode::set_environment()`.
+    pub fn set_environment(
+        &mut self,
+        humidity_percentage: f32,
+        temperature_celsius: f32,
+    ) -> Result<(), SupervisorError<E>> {
+        let result = self
+            .dev
+            .set_environment(humidity_percentage, temperature_celsius);
+        self.supervise(result, |dev| {
+            dev.set_environment(humidity_percentage, temperature_celsius)
+        })
+    }
+
+    /// Set the baseline. See `Ccs811AppMode::set_baseline()`.
+    pub fn set_baseline(&mut self, baseline: [u8; 2]) -> Result<(), SupervisorError<E>> {
+        let result = self.dev.set_baseline(baseline);
+        self.supervise(result, |dev| dev.set_baseline(baseline))
+    }
+
+    /// Check if there is a new data sample ready.
+    pub fn has_data_ready(&mut self) -> Result<bool, SupervisorError<E>> {
+        let result = self.dev.has_data_ready();
+        self.supervise(result, |_| Ok(()))
+    }
+
+    /// Get the algorithm results data.
+    pub fn data(&mut self) -> nb::Result<AlgorithmResult, SupervisorError<E>> {
+        match self.dev.data() {
+            Ok(data) => Ok(data),
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(e)) => Err(nb::Error::Other(self.handle_error(e, |_| Ok(())))),
+        }
+    }
+
+    /// Restart the application and reapply the configuration recorded by
+    /// the driver.
+    ///
+    /// This is done automatically when an unexpected reset is detected.
+    pub fn recover(&mut self) -> Result<(), E> {
+        self.recoveries = self.recoveries.wrapping_add(1);
+        self.dev.restart_application()?;
+        self.delay.delay_ms(1);
+        self.dev.reapply()
+    }
+
+    /// Recover if the operation failed because of an unexpected reset.
+    fn supervise<T, F>(&mut self, result: Result<T, E>, retry: F) -> Result<T, SupervisorError<E>>
+    where
+        F: FnOnce(&mut DEV) -> Result<(), E>,
+    {
+        result.map_err(|e| self.handle_error(e, retry))
+    }
+
+    /// Recover from an unexpected reset, doing the failed operation again.
+    fn handle_error<F>(&mut self, error: E, retry: F) -> SupervisorError<E>
+    where
+        F: FnOnce(&mut DEV) -> Result<(), E>,
+    {
+        if !error.is_unexpected_reset() {
+            return SupervisorError::Error(error);
+        }
+        match self.recover().and_then(|_| retry(&mut self.dev)) {
+            Ok(()) if self.dev.applied_config().is_none() => SupervisorError::Unconfigured,
+            Ok(()) => SupervisorError::Recovered,
+            Err(e) => SupervisorError::RecoveryFailed(e),
+        }
+    }
+}
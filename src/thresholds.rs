@@ -0,0 +1,249 @@
+use crate::AlgorithmResult;
+
+/// Hysteresis (ppm) applied by the device when the eCO2 moves between bands.
+pub const ECO2_HYSTERESIS: u16 = 50;
+/// Minimum eCO2 threshold value (ppm)
+const ECO2_THRESHOLD_MIN: u16 = 400;
+/// Maximum eCO2 threshold value (ppm)
+const ECO2_THRESHOLD_MAX: u16 = 8192;
+
+/// eCO2 thresholds for interrupt generation (in ppm).
+///
+/// The thresholds must be within 400-8192 ppm and `low_to_medium` must be
+/// lower than `medium_to_high`.
+/// See `Ccs811AppMode::set_eco2_thresholds_typed()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eco2Thresholds {
+    low_to_medium: u16,
+    medium_to_high: u16,
+}
+
+/// eCO2 band delimited by the `Eco2Thresholds`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Eco2Band {
+    /// Below the low to medium threshold.
+    Low,
+    /// Between the low to medium and the medium to high thresholds (inclusive).
+    Medium,
+    /// Above the medium to high threshold.
+    High,
+}
+
+/// Transition of the eCO2 value between bands.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eco2BandTransition {
+    /// Previous band
+    pub from: Eco2Band,
+    /// New band
+    pub to: Eco2Band,
+    /// eCO2 value which caused the transition (ppm)
+    pub eco2: u16,
+}
+
+impl Default for Eco2Thresholds {
+    /// Device default thresholds: 1500 ppm and 2500 ppm.
+    fn default() -> Self {
+        Eco2Thresholds {
+            low_to_medium: 1500,
+            medium_to_high: 2500,
+        }
+    }
+}
+
+impl Eco2Thresholds {
+    /// Create new thresholds.
+    ///
+    /// Returns `None` if the values are invalid.
+    pub fn new(low_to_medium: u16, medium_to_high: u16) -> Option<Self> {
+        if low_to_medium < ECO2_THRESHOLD_MIN
+            || medium_to_high > ECO2_THRESHOLD_MAX
+            || low_to_medium >= medium_to_high
+        {
+            None
+        } else {
+            Some(Eco2Thresholds {
+                low_to_medium,
+                medium_to_high,
+            })
+        }
+    }
+
+    /// Get the low to medium threshold (ppm).
+    pub fn low_to_medium(&self) -> u16 {
+        self.low_to_medium
+    }
+
+    /// Get the medium to high threshold (ppm).
+    pub fn medium_to_high(&self) -> u16 {
+        self.medium_to_high
+    }
+
+    /// Get the band an eCO2 value (ppm) falls into, without hysteresis.
+    pub fn band(&self, eco2: u16) -> Eco2Band {
+        if eco2 < self.low_to_medium {
+            Eco2Band::Low
+        } else if eco2 > self.medium_to_high {
+            Eco2Band::High
+        } else {
+            Eco2Band::Medium
+        }
+    }
+
+    /// Get the band an eCO2 value (ppm) falls into coming from the `current` band.
+    ///
+    /// As done by the device, the band only changes if the value moved
+    /// into another band by more than 50 ppm.
+    pub fn band_from(&self, current: Eco2Band, eco2: u16) -> Eco2Band {
+        let lower = eco2.saturating_add(ECO2_HYSTERESIS);
+        let upper = eco2.saturating_sub(ECO2_HYSTERESIS);
+        match self.band(eco2) {
+            Eco2Band::Low if current != Eco2Band::Low && lower >= self.low_to_medium => {
+                Eco2Band::Medium
+            }
+            Eco2Band::High if current != Eco2Band::High && upper <= self.medium_to_high => {
+                Eco2Band::Medium
+            }
+            Eco2Band::Medium => match current {
+                Eco2Band::Low if upper <= self.low_to_medium => Eco2Band::Low,
+                Eco2Band::High if lower >= self.medium_to_high => Eco2Band::High,
+                _ => Eco2Band::Medium,
+            },
+            band => band,
+        }
+    }
+}
+
+impl AlgorithmResult {
+    /// Get the band the eCO2 value falls into, without hysteresis.
+    pub fn eco2_band(&self, thresholds: &Eco2Thresholds) -> Eco2Band {
+        thresholds.band(self.eco2)
+    }
+}
+
+/// Tracker of the eCO2 band transitions of a device.
+///
+/// This follows the band as the device does for the
+/// `InterruptMode::OnThresholdCrossed` interrupt generation.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Eco2BandTracker {
+    thresholds: Eco2Thresholds,
+    band: Option<Eco2Band>,
+}
+
+impl Eco2BandTracker {
+    /// Create a new tracker.
+    pub fn new(thresholds: Eco2Thresholds) -> Self {
+        Eco2BandTracker {
+            thresholds,
+            band: None,
+        }
+    }
+
+    /// Get the current band, if any reading was processed.
+    pub fn band(&self) -> Option<Eco2Band> {
+        self.band
+    }
+
+    /// Process a reading and return the band transition, if any.
+    ///
+    /// The first reading only sets the current band.
+    pub fn update(&mut self, result: &AlgorithmResult) -> Option<Eco2BandTransition> {
+        let eco2 = result.eco2;
+        match self.band {
+            None => {
+                self.band = Some(self.thresholds.band(eco2));
+                None
+            }
+            Some(from) => {
+                let to = self.thresholds.band_from(from, eco2);
+                self.band = Some(to);
+                if from == to {
+                    None
+                } else {
+                    Some(Eco2BandTransition { from, to, eco2 })
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(eco2: u16) -> AlgorithmResult {
+        AlgorithmResult {
+            eco2,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn can_create_valid_thresholds() {
+        let thresholds = Eco2Thresholds::new(400, 8192).unwrap();
+        assert_eq!(400, thresholds.low_to_medium());
+        assert_eq!(8192, thresholds.medium_to_high());
+    }
+
+    #[test]
+    fn rejects_invalid_thresholds() {
+        assert_eq!(None, Eco2Thresholds::new(399, 2500));
+        assert_eq!(None, Eco2Thresholds::new(1500, 8193));
+        assert_eq!(None, Eco2Thresholds::new(2500, 1500));
+        assert_eq!(None, Eco2Thresholds::new(1500, 1500));
+    }
+
+    #[test]
+    fn classifies_values() {
+        let thresholds = Eco2Thresholds::default();
+        assert_eq!(Eco2Band::Low, result(1499).eco2_band(&thresholds));
+        assert_eq!(Eco2Band::Medium, result(1500).eco2_band(&thresholds));
+        assert_eq!(Eco2Band::Medium, result(2500).eco2_band(&thresholds));
+        assert_eq!(Eco2Band::High, result(2501).eco2_band(&thresholds));
+    }
+
+    #[test]
+    fn applies_hysteresis() {
+        let t = Eco2Thresholds::default();
+        assert_eq!(Eco2Band::Low, t.band_from(Eco2Band::Low, 1550));
+        assert_eq!(Eco2Band::Medium, t.band_from(Eco2Band::Low, 1551));
+        assert_eq!(Eco2Band::Medium, t.band_from(Eco2Band::Medium, 1450));
+        assert_eq!(Eco2Band::Low, t.band_from(Eco2Band::Medium, 1449));
+        assert_eq!(Eco2Band::Medium, t.band_from(Eco2Band::Medium, 2550));
+        assert_eq!(Eco2Band::High, t.band_from(Eco2Band::Medium, 2551));
+        assert_eq!(Eco2Band::High, t.band_from(Eco2Band::High, 2450));
+        assert_eq!(Eco2Band::Medium, t.band_from(Eco2Band::High, 2449));
+    }
+
+    #[test]
+    fn can_skip_medium_band() {
+        let t = Eco2Thresholds::default();
+        assert_eq!(Eco2Band::High, t.band_from(Eco2Band::Low, 3000));
+        assert_eq!(Eco2Band::Low, t.band_from(Eco2Band::High, 1000));
+    }
+
+    #[test]
+    fn reports_transitions() {
+        let mut tracker = Eco2BandTracker::new(Eco2Thresholds::default());
+        assert_eq!(None, tracker.update(&result(1000)));
+        assert_eq!(Some(Eco2Band::Low), tracker.band());
+        assert_eq!(None, tracker.update(&result(1520)));
+        assert_eq!(
+            Some(Eco2BandTransition {
+                from: Eco2Band::Low,
+                to: Eco2Band::Medium,
+                eco2: 1600
+            }),
+            tracker.update(&result(1600))
+        );
+        assert_eq!(None, tracker.update(&result(1480)));
+        assert_eq!(
+            Some(Eco2BandTransition {
+                from: Eco2Band::Medium,
+                to: Eco2Band::High,
+                eco2: 2600
+            }),
+            tracker.update(&result(2600))
+        );
+    }
+}
@@ -1,4 +1,7 @@
-use crate::{private, AlgorithmResult, FirmwareMode, InterruptMode, MeasurementMode};
+use crate::{
+    private, AlgorithmResult, Ccs811Config, Celsius, Clock, DeviceInfo, Eco2Thresholds,
+    FirmwareMode, InterruptMode, MeasurementMode, RelativeHumidity, TimedResult,
+};
 use embedded_hal::delay::DelayNs;
 
 /// General CCS811 methods available in either mode
@@ -23,6 +26,13 @@ pub trait Ccs811Device: private::Sealed {
 
     /// Get the firmware application verion (major, minor, trivial)
     fn firmware_application_version(&mut self) -> Result<(u8, u8, u8), Self::Error>;
+
+    /// Identify the device and get its firmware information.
+    ///
+    /// Returns `Error::UnexpectedIdentity` if the hardware ID is not 0x81 or
+    /// the hardware major version is not 1. In this case no further registers
+    /// are read.
+    fn identify(&mut self) -> Result<DeviceInfo, Self::Error>;
 }
 
 /// Methods available when on application mode
@@ -52,6 +62,28 @@ pub trait Ccs811AppMode: private::Sealed {
     /// The voltage contains the value as computed in the ADC. (1023 = 1.65V)
     fn data(&mut self) -> nb::Result<AlgorithmResult, Self::Error>;
 
+    /// Get the algorithm results data stamped with the time it was read.
+    ///
+    /// The time elapsed since the previous result read through this method
+    /// is included to spot missed measurement intervals.
+    fn data_timed<C: Clock>(&mut self, clock: &C) -> nb::Result<TimedResult, Self::Error>;
+
+    /// Wait for the algorithm results data.
+    ///
+    /// `data()` is polled every tenth of the sample period of the current
+    /// measurement mode, sleeping in between.
+    /// Returns `Error::Timeout` if no data is available after `timeout_ms`.
+    /// In `Idle` mode no new data is produced so `Error::Timeout` is
+    /// returned right away if no data is available.
+    /// In `ConstantPower250ms` mode the algorithm results are not updated so
+    /// `Error::InvalidInputData` is returned. If the measurement mode is not
+    /// known since the application was started, it is read from the device.
+    fn read_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<AlgorithmResult, Self::Error>;
+
     /// Get the raw sensor data.
     ///
     /// Returns a tuple containing the current and voltage through the sensor in
@@ -76,6 +108,17 @@ pub trait Ccs811AppMode: private::Sealed {
         temperature_celsius: f32,
     ) -> Result<(), Self::Error>;
 
+    /// Set the environment temperature and relative humidity with physical units.
+    ///
+    /// See `set_environment()`.
+    fn set_environment_typed(
+        &mut self,
+        humidity: RelativeHumidity,
+        temperature: Celsius,
+    ) -> Result<(), Self::Error> {
+        self.set_environment(humidity.0, temperature.0)
+    }
+
     /// Configure the interrupt generation.
     fn set_interrupt_mode(&mut self, mode: InterruptMode) -> Result<(), Self::Error>;
 
@@ -83,16 +126,56 @@ pub trait Ccs811AppMode: private::Sealed {
     ///
     /// An interrupt will be asserted if the value moved from the current
     /// range by 50 ppm.
+    ///
+    /// The values are written as given. Only values accepted by
+    /// `Eco2Thresholds::new()` are recorded in the configuration.
+    /// See `set_eco2_thresholds_typed()`.
     fn set_eco2_thresholds(
         &mut self,
         low_to_medium: u16,
         medium_to_high: u16,
     ) -> Result<(), Self::Error>;
 
+    /// Set validated eCO2 threshold values for interrupt generation.
+    ///
+    /// See `set_eco2_thresholds()`.
+    fn set_eco2_thresholds_typed(&mut self, thresholds: Eco2Thresholds) -> Result<(), Self::Error> {
+        self.set_eco2_thresholds(thresholds.low_to_medium(), thresholds.medium_to_high())
+    }
+
+    /// Apply a complete configuration.
+    ///
+    /// The environment data is validated before anything is written.
+    /// Returns `Error::InvalidInputData` if it is out of range.
+    /// The eCO2 thresholds, environment data, measurement and interrupt
+    /// modes and the baseline are then written in this order. The configuration
+    /// is recorded by the driver once all writes succeed and kept across
+    /// mode changes. If any write fails, the previous record is kept.
+    /// Later changes through the individual setters update the record.
+    /// If no configuration was applied, the first setter creates the record
+    /// from the current measurement and interrupt modes.
+    fn apply(&mut self, config: &Ccs811Config) -> Result<(), Self::Error>;
+
+    /// Get the recorded configuration, if any.
+    fn applied_config(&self) -> Option<Ccs811Config>;
+
+    /// Apply the recorded configuration again.
+    ///
+    /// This restores the configuration after a reset and application start.
+    /// Nothing is done if no configuration was recorded.
+    fn reapply(&mut self) -> Result<(), Self::Error>;
+
     /// Restart the device in boot mode.
     ///
     /// 2ms should be waited before doing any other operation.
     fn software_reset(self) -> Result<Self::BootModeType, Self::ModeChangeError>;
+
+    /// Restart the application after an unexpected reset into boot mode.
+    ///
+    /// This is needed after getting an `UnexpectedReset` error. The
+    /// device configuration is lost on reset so it must be set again.
+    /// 1ms should be waited before doing any other operation.
+    fn restart_application(&mut self) -> Result<(), Self::Error>;
 }
 
 /// Methods available when on boot mode
@@ -126,12 +209,32 @@ pub trait Ccs811BootMode: private::Sealed {
     /// poll until completion.
     fn verify_application(&mut self) -> nb::Result<(), Self::Error>;
 
+    /// Verify application and wait for the verification to complete.
+    ///
+    /// The verification is polled every 70ms.
+    /// Returns `Error::Timeout` if it did not complete after `timeout_ms`.
+    fn verify_application_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<(), Self::Error>;
+
     /// Erase application.
     ///
     /// NOTE: After the first call, 500ms must be waited before calling again to
     /// poll until completion.
     fn erase_application(&mut self) -> nb::Result<(), Self::Error>;
 
+    /// Erase application and wait for the erase to complete.
+    ///
+    /// The erase is polled every 500ms.
+    /// Returns `Error::Timeout` if it did not complete after `timeout_ms`.
+    fn erase_application_blocking<D: DelayNs>(
+        &mut self,
+        delay: &mut D,
+        timeout_ms: u32,
+    ) -> Result<(), Self::Error>;
+
     /// Download new application.
     ///
     /// Returns `Error::InvalidInputData` if the input binary lengh is not multiple of 8.
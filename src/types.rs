@@ -1,3 +1,4 @@
+use crate::Eco2Thresholds;
 use core::convert::From;
 
 /// All possible errors generated when using the `Ccs811` type.
@@ -17,6 +18,19 @@ pub enum Error<CommE, PinE> {
     NoValidApp,
     /// Invalid input data provided to function
     InvalidInputData,
+    /// Unexpected device identity. The device is probably not a CCS811.
+    ///
+    /// Contains the hardware ID and hardware version (major, minor) read.
+    UnexpectedIdentity(u8, (u8, u8)),
+    /// The device unexpectedly returned to boot mode, for example after a
+    /// brown-out. The application must be restarted.
+    ///
+    /// Detected when reading the data or the status, and after writing a
+    /// setting unless the status check after writes is disabled.
+    /// See `StatusCheck` and `Ccs811AppMode::restart_application()`.
+    UnexpectedReset,
+    /// The operation did not complete within the given timeout.
+    Timeout,
 }
 
 /// All possible errors when using an the `Ccs811Awake` type.
@@ -34,6 +48,19 @@ pub enum ErrorAwake<E> {
     NoValidApp,
     /// Invalid input data provided to function
     InvalidInputData,
+    /// Unexpected device identity. The device is probably not a CCS811.
+    ///
+    /// Contains the hardware ID and hardware version (major, minor) read.
+    UnexpectedIdentity(u8, (u8, u8)),
+    /// The device unexpectedly returned to boot mode, for example after a
+    /// brown-out. The application must be restarted.
+    ///
+    /// Detected when reading the data or the status, and after writing a
+    /// setting unless the status check after writes is disabled.
+    /// See `StatusCheck` and `Ccs811AppMode::restart_application()`.
+    UnexpectedReset,
+    /// The operation did not complete within the given timeout.
+    Timeout,
 }
 
 impl<CommE, PinE> From<ErrorAwake<CommE>> for Error<CommE, PinE> {
@@ -43,6 +70,9 @@ impl<CommE, PinE> From<ErrorAwake<CommE>> for Error<CommE, PinE> {
             ErrorAwake::Device(e) => Error::Device(e),
             ErrorAwake::NoValidApp => Error::NoValidApp,
             ErrorAwake::InvalidInputData => Error::InvalidInputData,
+            ErrorAwake::UnexpectedIdentity(id, version) => Error::UnexpectedIdentity(id, version),
+            ErrorAwake::UnexpectedReset => Error::UnexpectedReset,
+            ErrorAwake::Timeout => Error::Timeout,
         }
     }
 }
@@ -100,6 +130,26 @@ pub enum MeasurementMode {
     ConstantPower250ms,
 }
 
+/// Status check policy
+///
+/// Controls when the driver reads the STATUS register (and ERROR_ID, if
+/// an error is flagged) to report errors after each register access.
+/// The device latches errors in ERROR_ID until it is read, so errors
+/// are not lost when the check is deferred.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum StatusCheck {
+    /// Read STATUS after every register access. (default)
+    #[default]
+    Always,
+    /// Do not read STATUS after register accesses. Errors are reported by
+    /// the next operation that reads STATUS or the algorithm result data.
+    Deferred,
+    /// Do not read STATUS after register accesses and do not read ERROR_ID
+    /// when reading STATUS. Only errors contained in the algorithm result
+    /// data are reported.
+    Never,
+}
+
 /// Firmware mode
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum FirmwareMode {
@@ -109,6 +159,23 @@ pub enum FirmwareMode {
     Application,
 }
 
+/// Device identity and firmware information
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeviceInfo {
+    /// Hardware ID (0x81 for the CCS81x family of devices)
+    pub hardware_id: u8,
+    /// Hardware version (major, minor)
+    pub hardware_version: (u8, u8),
+    /// Firmware bootloader version (major, minor, trivial)
+    pub bootloader_version: (u8, u8, u8),
+    /// Firmware application version (major, minor, trivial)
+    pub application_version: (u8, u8, u8),
+    /// Whether a valid application firmware is loaded
+    pub has_valid_app: bool,
+    /// Current firmware mode
+    pub firmware_mode: FirmwareMode,
+}
+
 /// Interrupt generation modes.
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub enum InterruptMode {
@@ -121,6 +188,36 @@ pub enum InterruptMode {
     OnThresholdCrossed,
 }
 
+/// Complete device configuration in application mode.
+///
+/// See `Ccs811AppMode::apply()`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Ccs811Config {
+    /// Measurement mode
+    pub mode: MeasurementMode,
+    /// Interrupt generation mode
+    pub interrupt_mode: InterruptMode,
+    /// eCO2 thresholds for interrupt generation. Not written if `None`.
+    pub eco2_thresholds: Option<Eco2Thresholds>,
+    /// Environment relative humidity (%) and temperature (ºC). Not written if `None`.
+    pub environment: Option<(f32, f32)>,
+    /// Baseline. Not written if `None`.
+    pub baseline: Option<[u8; 2]>,
+}
+
+impl Default for Ccs811Config {
+    /// `ConstantPower1s` measurement mode with interrupts disabled.
+    fn default() -> Self {
+        Ccs811Config {
+            mode: MeasurementMode::ConstantPower1s,
+            interrupt_mode: InterruptMode::Disabled,
+            eco2_thresholds: None,
+            environment: None,
+            baseline: None,
+        }
+    }
+}
+
 /// Algorithm result
 #[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct AlgorithmResult {
@@ -134,8 +231,39 @@ pub struct AlgorithmResult {
     pub raw_voltage: u16,
 }
 
+/// Algorithm result stamped with the time it was read.
+///
+/// See `Ccs811AppMode::data_timed()`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TimedResult {
+    /// Algorithm result
+    pub result: AlgorithmResult,
+    /// Time (ms) at which the result was read
+    pub timestamp_ms: u32,
+    /// Time (ms) elapsed since the previous result was read, if any.
+    ///
+    /// This is reset when the measurement mode is set, when the application
+    /// is restarted and on firmware mode changes.
+    pub since_previous_ms: Option<u32>,
+}
+
+/// Algorithm result with a corrected eCO2 value.
+///
+/// See `Eco2Calibration::apply()`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct CalibratedResult {
+    /// Algorithm result as returned by the device
+    pub result: AlgorithmResult,
+    /// eCO2 (ppm) after applying the calibration
+    pub corrected_eco2: u16,
+}
+
+/// Placeholder for an optional pin which is not connected.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct NoPin;
+
 /// Possible slave addresses
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum SlaveAddr {
     /// Default slave address
     Default,
@@ -0,0 +1,261 @@
+use crate::AlgorithmResult;
+
+/// Concentration in parts per million.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Ppm(pub u16);
+
+/// Concentration in parts per billion.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct Ppb(pub u16);
+
+/// Relative humidity in percent [0.0..100.0].
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct RelativeHumidity(pub f32);
+
+/// Absolute humidity in grams of water vapor per cubic meter of air.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct AbsoluteHumidity(pub f32);
+
+/// Temperature in degrees Celsius.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Celsius(pub f32);
+
+/// Current in microamps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default)]
+pub struct MicroAmps(pub u8);
+
+/// Voltage in volts.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Volts(pub f32);
+
+/// Full scale of the raw voltage ADC (V)
+const ADC_FULL_SCALE_VOLTS: f32 = 1.65;
+/// Maximum raw voltage ADC value
+const ADC_MAX: u16 = 1023;
+
+impl Ppm {
+    /// Get the concentration in parts per billion, if it fits.
+    pub fn to_ppb(self) -> Option<Ppb> {
+        self.0.checked_mul(1000).map(Ppb)
+    }
+}
+
+impl Ppb {
+    /// Get the concentration in parts per million, truncated.
+    pub fn to_ppm(self) -> Ppm {
+        Ppm(self.0 / 1000)
+    }
+}
+
+impl Celsius {
+    /// Create from a temperature in Kelvin.
+    pub fn from_kelvin(kelvin: f32) -> Self {
+        Celsius(kelvin - 273.15)
+    }
+
+    /// Get the temperature in Kelvin.
+    pub fn kelvin(self) -> f32 {
+        self.0 + 273.15
+    }
+
+    /// Create from a temperature in degrees Fahrenheit.
+    pub fn from_fahrenheit(fahrenheit: f32) -> Self {
+        Celsius((fahrenheit - 32.0) * 5.0 / 9.0)
+    }
+
+    /// Get the temperature in degrees Fahrenheit.
+    pub fn fahrenheit(self) -> f32 {
+        self.0 * 9.0 / 5.0 + 32.0
+    }
+}
+
+impl Volts {
+    /// Create from a raw voltage ADC value (1023 = 1.65V).
+    pub fn from_raw(raw: u16) -> Self {
+        Volts(f32::from(raw) * ADC_FULL_SCALE_VOLTS / f32::from(ADC_MAX))
+    }
+}
+
+macro_rules! impl_raw_conversions {
+    ($unit:ident, $raw:ty) => {
+        impl From<$raw> for $unit {
+            fn from(value: $raw) -> Self {
+                $unit(value)
+            }
+        }
+
+        impl From<$unit> for $raw {
+            fn from(value: $unit) -> Self {
+                value.0
+            }
+        }
+    };
+}
+
+impl_raw_conversions!(Ppm, u16);
+impl_raw_conversions!(Ppb, u16);
+impl_raw_conversions!(RelativeHumidity, f32);
+impl_raw_conversions!(AbsoluteHumidity, f32);
+impl_raw_conversions!(Celsius, f32);
+impl_raw_conversions!(MicroAmps, u8);
+impl_raw_conversions!(Volts, f32);
+
+/// Algorithm result with physical units.
+///
+/// See `AlgorithmResult::measurement()`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Measurement {
+    /// eCO2
+    pub eco2: Ppm,
+    /// eTVOC
+    pub etvoc: Ppb,
+    /// Raw sensor current
+    pub raw_current: MicroAmps,
+    /// Raw sensor voltage
+    pub raw_voltage: Volts,
+}
+
+impl AlgorithmResult {
+    /// Get the eCO2.
+    pub fn eco2_ppm(&self) -> Ppm {
+        Ppm(self.eco2)
+    }
+
+    /// Get the eTVOC.
+    pub fn etvoc_ppb(&self) -> Ppb {
+        Ppb(self.etvoc)
+    }
+
+    /// Get the raw sensor current.
+    pub fn raw_current_ua(&self) -> MicroAmps {
+        MicroAmps(self.raw_current)
+    }
+
+    /// Get the raw sensor voltage.
+    pub fn raw_voltage_v(&self) -> Volts {
+        Volts::from_raw(self.raw_voltage)
+    }
+
+    /// Get the result with physical units.
+    pub fn measurement(&self) -> Measurement {
+        Measurement {
+            eco2: self.eco2_ppm(),
+            etvoc: self.etvoc_ppb(),
+            raw_current: self.raw_current_ua(),
+            raw_voltage: self.raw_voltage_v(),
+        }
+    }
+}
+
+impl From<AlgorithmResult> for Measurement {
+    fn from(result: AlgorithmResult) -> Self {
+        result.measurement()
+    }
+}
+
+#[cfg(feature = "uom")]
+mod uom_conversions {
+    use super::{Celsius, MicroAmps, Ppb, Ppm, RelativeHumidity, Volts};
+    use uom::si::{
+        electric_current::microampere,
+        electric_potential::volt,
+        f32::{ElectricCurrent, ElectricPotential, Ratio, ThermodynamicTemperature},
+        ratio::{part_per_billion, part_per_million, percent},
+        thermodynamic_temperature::degree_celsius,
+    };
+
+    impl From<Ppm> for Ratio {
+        fn from(value: Ppm) -> Self {
+            Ratio::new::<part_per_million>(f32::from(value.0))
+        }
+    }
+
+    impl From<Ppb> for Ratio {
+        fn from(value: Ppb) -> Self {
+            Ratio::new::<part_per_billion>(f32::from(value.0))
+        }
+    }
+
+    impl From<RelativeHumidity> for Ratio {
+        fn from(value: RelativeHumidity) -> Self {
+            Ratio::new::<percent>(value.0)
+        }
+    }
+
+    impl From<Ratio> for RelativeHumidity {
+        fn from(value: Ratio) -> Self {
+            RelativeHumidity(value.get::<percent>())
+        }
+    }
+
+    impl From<Celsius> for ThermodynamicTemperature {
+        fn from(value: Celsius) -> Self {
+            ThermodynamicTemperature::new::<degree_celsius>(value.0)
+        }
+    }
+
+    impl From<ThermodynamicTemperature> for Celsius {
+        fn from(value: ThermodynamicTemperature) -> Self {
+            Celsius(value.get::<degree_celsius>())
+        }
+    }
+
+    impl From<MicroAmps> for ElectricCurrent {
+        fn from(value: MicroAmps) -> Self {
+            ElectricCurrent::new::<microampere>(f32::from(value.0))
+        }
+    }
+
+    impl From<Volts> for ElectricPotential {
+        fn from(value: Volts) -> Self {
+            ElectricPotential::new::<volt>(value.0)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_convert_concentrations() {
+        assert_eq!(Some(Ppb(2000)), Ppm(2).to_ppb());
+        assert_eq!(None, Ppm(66).to_ppb());
+        assert_eq!(Ppm(1), Ppb(1999).to_ppm());
+    }
+
+    #[test]
+    fn can_convert_temperatures() {
+        assert_eq!(Celsius(0.0), Celsius::from_kelvin(273.15));
+        assert_eq!(293.15, Celsius(20.0).kelvin());
+        assert_eq!(Celsius(100.0), Celsius::from_fahrenheit(212.0));
+        assert_eq!(212.0, Celsius(100.0).fahrenheit());
+    }
+
+    #[test]
+    fn can_convert_raw_values() {
+        assert_eq!(Ppm(400), Ppm::from(400));
+        assert_eq!(400u16, u16::from(Ppm(400)));
+        assert_eq!(Volts(1.65), Volts::from_raw(1023));
+        assert_eq!(Volts(0.0), Volts::from_raw(0));
+    }
+
+    #[test]
+    fn can_get_measurement() {
+        let result = AlgorithmResult {
+            eco2: 450,
+            etvoc: 12,
+            raw_current: 20,
+            raw_voltage: 1023,
+        };
+        assert_eq!(
+            Measurement {
+                eco2: Ppm(450),
+                etvoc: Ppb(12),
+                raw_current: MicroAmps(20),
+                raw_voltage: Volts(1.65),
+            },
+            Measurement::from(result)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use crate::hal::{digital::OutputPin, pwm::SetDutyCycle};
+use crate::{private, AlgorithmResult};
+
+/// Switching thresholds with hysteresis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Hysteresis {
+    /// Value at or above which ventilation is switched on
+    pub on: u16,
+    /// Value below which ventilation may be switched off
+    pub off: u16,
+}
+
+/// Configuration of a `VentilationController`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VentilationConfig {
+    /// eCO2 thresholds (ppm). Default: on at 1000 ppm, off below 800 ppm.
+    pub eco2: Option<Hysteresis>,
+    /// eTVOC thresholds (ppb). Default: on at 500 ppb, off below 250 ppb.
+    pub etvoc: Option<Hysteresis>,
+    /// Minimum time (ms) ventilation stays on. Default: 5 minutes.
+    pub min_on_ms: u32,
+    /// Minimum time (ms) ventilation stays off. Default: 2 minutes.
+    pub min_off_ms: u32,
+}
+
+impl Default for VentilationConfig {
+    fn default() -> Self {
+        VentilationConfig {
+            eco2: Some(Hysteresis { on: 1000, off: 800 }),
+            etvoc: Some(Hysteresis { on: 500, off: 250 }),
+            min_on_ms: 5 * 60 * 1000,
+            min_off_ms: 2 * 60 * 1000,
+        }
+    }
+}
+
+/// Manual override of a `VentilationController`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum VentilationOverride {
+    /// Switch according to the readings. (default)
+    #[default]
+    Auto,
+    /// Keep ventilation on.
+    On,
+    /// Keep ventilation off.
+    Off,
+}
+
+/// Output switched by a `VentilationController`.
+///
+/// This is implemented for output pins, which are set high when
+/// ventilation is on, and for `PwmFan`.
+pub trait FanOutput {
+    /// Error type
+    type Error;
+
+    /// Switch the fan on or off.
+    fn set_fan(&mut self, on: bool) -> Result<(), Self::Error>;
+}
+
+impl<P: OutputPin> FanOutput for P {
+    type Error = P::Error;
+
+    fn set_fan(&mut self, on: bool) -> Result<(), Self::Error> {
+        private::set_pin(self, on)
+    }
+}
+
+/// Fan driven by a PWM output with a fixed duty cycle when on.
+#[derive(Debug)]
+pub struct PwmFan<PWM> {
+    pwm: PWM,
+    on_percent: u8,
+}
+
+impl<PWM: SetDutyCycle> PwmFan<PWM> {
+    /// Create a new PWM fan running at `on_percent` duty cycle when on.
+    pub fn new(pwm: PWM, on_percent: u8) -> Self {
+        PwmFan { pwm, on_percent }
+    }
+
+    /// Destroy the fan and return the PWM output.
+    pub fn destroy(self) -> PWM {
+        self.pwm
+    }
+}
+
+impl<PWM: SetDutyCycle> FanOutput for PwmFan<PWM> {
+    type Error = PWM::Error;
+
+    fn set_fan(&mut self, on: bool) -> Result<(), Self::Error> {
+        if on {
+            self.pwm.set_duty_cycle_percent(self.on_percent)
+        } else {
+            self.pwm.set_duty_cycle_fully_off()
+        }
+    }
+}
+
+/// Ventilation controller switching a fan or window actuator based on the
+/// readings.
+///
+/// Ventilation is switched on when any reading reaches its `on` threshold
+/// and switched off when all readings are below their `off` threshold,
+/// respecting the minimum on and off times. The override takes effect
+/// immediately.
+///
+/// The readings are fed together with a timestamp in milliseconds from
+/// any monotonic clock. The timestamps may wrap around.
+#[derive(Debug)]
+pub struct VentilationController<OUT> {
+    output: OUT,
+    config: VentilationConfig,
+    override_mode: VentilationOverride,
+    on: bool,
+    written: bool,
+    changed_ms: Option<u32>,
+}
+
+impl<OUT: FanOutput> VentilationController<OUT> {
+    /// Create a new controller. Ventilation is initially off and the
+    /// output is written on the first update.
+    pub fn new(output: OUT, config: VentilationConfig) -> Self {
+        VentilationController {
+            output,
+            config,
+            override_mode: VentilationOverride::Auto,
+            on: false,
+            written: false,
+            changed_ms: None,
+        }
+    }
+
+    /// Destroy the controller and return the output.
+    pub fn destroy(self) -> OUT {
+        self.output
+    }
+
+    /// Whether ventilation is on.
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Get the current override.
+    pub fn override_mode(&self) -> VentilationOverride {
+        self.override_mode
+    }
+
+    /// Set the override.
+    ///
+    /// `On` and `Off` switch the output immediately. With `Auto` the
+    /// readings control the output again from the next update.
+    pub fn set_override(
+        &mut self,
+        override_mode: VentilationOverride,
+        now_ms: u32,
+    ) -> Result<bool, OUT::Error> {
+        self.override_mode = override_mode;
+        match override_mode {
+            VentilationOverride::Auto => Ok(self.on),
+            VentilationOverride::On => self.switch(true, now_ms),
+            VentilationOverride::Off => self.switch(false, now_ms),
+        }
+    }
+
+    /// Feed a new reading and update the output.
+    ///
+    /// Returns whether ventilation is on.
+    pub fn update(&mut self, result: &AlgorithmResult, now_ms: u32) -> Result<bool, OUT::Error> {
+        let on = match self.override_mode {
+            VentilationOverride::On => true,
+            VentilationOverride::Off => false,
+            VentilationOverride::Auto => {
+                let demand = self.demand(result);
+                if demand != self.on && self.min_time_elapsed(now_ms) {
+                    demand
+                } else {
+                    self.on
+                }
+            }
+        };
+        self.switch(on, now_ms)
+    }
+
+    fn demand(&self, result: &AlgorithmResult) -> bool {
+        let readings = [
+            (self.config.eco2, result.eco2),
+            (self.config.etvoc, result.etvoc),
+        ];
+        if self.on {
+            readings.iter().any(|(thresholds, value)| match thresholds {
+                Some(thresholds) => *value >= thresholds.off,
+                None => false,
+            })
+        } else {
+            readings.iter().any(|(thresholds, value)| match thresholds {
+                Some(thresholds) => *value >= thresholds.on,
+                None => false,
+            })
+        }
+    }
+
+    fn min_time_elapsed(&self, now_ms: u32) -> bool {
+        let min_ms = if self.on {
+            self.config.min_on_ms
+        } else {
+            self.config.min_off_ms
+        };
+        match self.changed_ms {
+            Some(changed_ms) => now_ms.wrapping_sub(changed_ms) >= min_ms,
+            None => true,
+        }
+    }
+
+    fn switch(&mut self, on: bool, now_ms: u32) -> Result<bool, OUT::Error> {
+        if on != self.on || !self.written {
+            self.output.set_fan(on)?;
+            if on != self.on {
+                self.changed_ms = Some(now_ms);
+            }
+            self.on = on;
+            self.written = true;
+        }
+        Ok(on)
+    }
+}
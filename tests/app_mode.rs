@@ -1,5 +1,6 @@
 use embedded_ccs811::{
-    mode, prelude::*, AlgorithmResult, Ccs811, Error, InterruptMode, MeasurementMode,
+    mode, prelude::*, AlgorithmResult, Ccs811, Celsius, Eco2Thresholds, Error, InterruptMode,
+    MeasurementMode, RelativeHumidity,
 };
 use embedded_hal_mock::eh1::{
     delay::NoopDelay as NoDelay,
@@ -33,7 +34,7 @@ macro_rules! set_test {
                 I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
                 // started
                 I2cTrans::write(DEV_ADDR, vec![Register::$reg, $reg_value]),
-                I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+                I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
             ];
             let mut sensor = new_app(&transactions, nwake);
             sensor.$method($value).unwrap();
@@ -79,8 +80,13 @@ macro_rules! read_status_app_test {
     };
 }
 
-read_status_app_test!(has_data_ready, has_data_ready, true, BF::DATA_READY);
-read_status_app_test!(has_no_data_ready, has_data_ready, false, 0);
+read_status_app_test!(
+    has_data_ready,
+    has_data_ready,
+    true,
+    BF::FW_MODE | BF::DATA_READY
+);
+read_status_app_test!(has_no_data_ready, has_data_ready, false, BF::FW_MODE);
 
 #[test]
 fn can_read_raw_data() {
@@ -121,12 +127,21 @@ fn can_read_alg_result_data() {
         I2cTrans::write_read(
             DEV_ADDR,
             vec![Register::ALG_RESULT_DATA],
-            vec![0, 0, 0, 0, 0, 0, 0, 0],
+            vec![0, 0, 0, 0, BF::FW_MODE, 0, 0, 0],
         ),
         I2cTrans::write_read(
             DEV_ADDR,
             vec![Register::ALG_RESULT_DATA],
-            vec![0x12, 0x34, 0x56, 0x78, BF::DATA_READY, 0, 0x91, 0x52],
+            vec![
+                0x12,
+                0x34,
+                0x56,
+                0x78,
+                BF::FW_MODE | BF::DATA_READY,
+                0,
+                0x91,
+                0x52,
+            ],
         ),
     ];
     let mut sensor = new_app(&transactions, nwake);
@@ -177,7 +192,7 @@ fn can_set_baseline() {
         I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
         // started
         I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
-        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
     ];
     let mut sensor = new_app(&transactions, nwake);
     sensor.set_baseline([0x34, 0x52]).unwrap();
@@ -226,13 +241,36 @@ fn can_set_environment_params() {
         I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
         // started
         I2cTrans::write(DEV_ADDR, vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]),
-        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
     ];
     let mut sensor = new_app(&transactions, nwake);
     sensor.set_environment(48.25, 25.125).unwrap();
     destroy(sensor);
 }
 
+#[test]
+fn can_set_environment_params_with_units() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // started
+        I2cTrans::write(DEV_ADDR, vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ];
+    let mut sensor = new_app(&transactions, nwake);
+    sensor
+        .set_environment_typed(RelativeHumidity(48.25), Celsius(25.125))
+        .unwrap();
+    destroy(sensor);
+}
+
 #[test]
 fn can_set_thresholds() {
     let nwake = PinMock::new(&[
@@ -247,13 +285,61 @@ fn can_set_thresholds() {
         I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
         // started
         I2cTrans::write(DEV_ADDR, vec![Register::THRESHOLDS, 0x05, 0xDC, 0x09, 0xC4]),
-        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
     ];
     let mut sensor = new_app(&transactions, nwake);
     sensor.set_eco2_thresholds(1500, 2500).unwrap();
     destroy(sensor);
 }
 
+#[test]
+fn can_set_unvalidated_thresholds() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // started
+        I2cTrans::write(DEV_ADDR, vec![Register::THRESHOLDS, 0x09, 0xC4, 0x05, 0xDC]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ];
+    let mut sensor = new_app(&transactions, nwake);
+    sensor.set_eco2_thresholds(2500, 1500).unwrap();
+    assert_eq!(None, sensor.applied_config().unwrap().eco2_thresholds);
+    destroy(sensor);
+}
+
+#[test]
+fn can_set_typed_thresholds() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // started
+        I2cTrans::write(DEV_ADDR, vec![Register::THRESHOLDS, 0x05, 0xDC, 0x09, 0xC4]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ];
+    let mut sensor = new_app(&transactions, nwake);
+    let thresholds = Eco2Thresholds::new(1500, 2500).unwrap();
+    sensor.set_eco2_thresholds_typed(thresholds).unwrap();
+    assert_eq!(
+        Some(thresholds),
+        sensor.applied_config().unwrap().eco2_thresholds
+    );
+    destroy(sensor);
+}
+
 macro_rules! set_int_test {
     ($name:ident, $mode:ident, $value:expr) => {
         set_test!(
@@ -289,3 +375,72 @@ fn can_do_software_reset() {
     let sensor = sensor.software_reset().ok().unwrap();
     destroy(sensor);
 }
+
+#[test]
+fn detects_unexpected_reset_in_data() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // started
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::ALG_RESULT_DATA],
+            vec![0, 0, 0, 0, BF::APP_VALID, 0, 0, 0],
+        ),
+    ];
+    let mut sensor = new_app(&transactions, nwake);
+    match sensor.data() {
+        Err(NbError::Other(Error::UnexpectedReset)) => (),
+        _ => panic!("Wrong result"),
+    }
+    destroy(sensor);
+}
+
+#[test]
+fn detects_unexpected_reset_in_status() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // started
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+    ];
+    let mut sensor = new_app(&transactions, nwake);
+    assert_error!(sensor.has_data_ready(), Error::UnexpectedReset);
+    destroy(sensor);
+}
+
+#[test]
+fn can_restart_application() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // started
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ];
+    let mut sensor = new_app(&transactions, nwake);
+    sensor.restart_application().unwrap();
+    destroy(sensor);
+}
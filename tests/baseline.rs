@@ -0,0 +1,124 @@
+use core::cell::Cell;
+use embedded_ccs811::{
+    BaselineEvent, BaselineScheduleConfig, BaselineScheduler, DeviceErrors, ErrorAwake,
+};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+mod common;
+use crate::common::{destroy_awake, new_awake_app, read_baseline, write, Register, DEV_ADDR};
+
+const CONFIG: BaselineScheduleConfig = BaselineScheduleConfig {
+    burn_in_ms: 10_000,
+    warm_up_ms: 1_000,
+    save_period_ms: 5_000,
+};
+
+#[test]
+fn saves_after_burn_in_and_periodically() {
+    let mut transactions = read_baseline([0x12, 0x34]);
+    transactions.extend(read_baseline([0x56, 0x78]));
+    let mut sensor = new_awake_app(&transactions);
+    let now = Cell::new(0);
+    let mut scheduler = BaselineScheduler::new(|| now.get(), CONFIG, None);
+    assert!(scheduler.is_warming_up());
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    now.set(9_999);
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    assert!(!scheduler.is_burned_in());
+    now.set(10_000);
+    assert_eq!(
+        Some(BaselineEvent::Save([0x12, 0x34])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    assert!(scheduler.is_burned_in());
+    now.set(14_999);
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    now.set(15_000);
+    assert_eq!(
+        Some(BaselineEvent::Save([0x56, 0x78])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    assert_eq!(Some([0x56, 0x78]), scheduler.saved_baseline());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn restores_after_warm_up() {
+    let mut transactions = write(vec![Register::BASELINE, 0x12, 0x34]);
+    transactions.extend(read_baseline([0x56, 0x78]));
+    transactions.extend(write(vec![Register::BASELINE, 0x56, 0x78]));
+    let mut sensor = new_awake_app(&transactions);
+    let now = Cell::new(u32::MAX - 499);
+    let mut scheduler = BaselineScheduler::new(|| now.get(), CONFIG, Some([0x12, 0x34]));
+    assert!(scheduler.is_burned_in());
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    now.set(500);
+    assert_eq!(
+        Some(BaselineEvent::Restored([0x12, 0x34])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    now.set(4_500);
+    assert_eq!(
+        Some(BaselineEvent::Save([0x56, 0x78])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    now.set(6_000);
+    scheduler.restart();
+    assert!(scheduler.is_warming_up());
+    now.set(6_999);
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    now.set(7_000);
+    assert_eq!(
+        Some(BaselineEvent::Restored([0x56, 0x78])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    now.set(10_999);
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn retries_failed_restore() {
+    let mut transactions = vec![
+        I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x12, 0x34]).with_error(ErrorKind::Bus),
+    ];
+    transactions.extend(write(vec![Register::BASELINE, 0x12, 0x34]));
+    transactions.extend(read_baseline([0x56, 0x78]));
+    let mut sensor = new_awake_app(&transactions);
+    let now = Cell::new(0);
+    let mut scheduler = BaselineScheduler::new(|| now.get(), CONFIG, Some([0x12, 0x34]));
+    now.set(5_000);
+    match scheduler.poll(&mut sensor) {
+        Err(ErrorAwake::I2C(_)) => (),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(
+        Some(BaselineEvent::Restored([0x12, 0x34])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    assert_eq!(
+        Some(BaselineEvent::Save([0x56, 0x78])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    destroy_awake(sensor);
+}
+
+#[test]
+fn refuses_to_save_with_device_errors() {
+    let mut sensor = new_awake_app(&read_baseline([0x12, 0x34]));
+    let now = Cell::new(0);
+    let mut scheduler = BaselineScheduler::new(|| now.get(), CONFIG, None);
+    scheduler.on_device_errors(&DeviceErrors {
+        heater_fault: true,
+        ..Default::default()
+    });
+    now.set(10_000);
+    assert_eq!(None, scheduler.poll(&mut sensor).unwrap());
+    scheduler.on_device_errors(&DeviceErrors::default());
+    assert_eq!(
+        Some(BaselineEvent::Save([0x12, 0x34])),
+        scheduler.poll(&mut sensor).unwrap()
+    );
+    destroy_awake(sensor);
+}
@@ -0,0 +1,165 @@
+use embedded_ccs811::{prelude::*, Error, ErrorAwake, MeasurementMode};
+use embedded_hal::delay::DelayNs;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+mod common;
+use crate::common::{
+    alg_result, destroy, destroy_awake, new, new_awake, start_application, wake_cycles, write,
+    BitFlags as BF, Register, DEV_ADDR,
+};
+
+#[derive(Default)]
+struct RecordingDelay {
+    waits_ms: Vec<u32>,
+}
+
+impl DelayNs for RecordingDelay {
+    fn delay_ns(&mut self, ns: u32) {
+        self.waits_ms.push(ns / 1_000_000);
+    }
+
+    fn delay_ms(&mut self, ms: u32) {
+        self.waits_ms.push(ms);
+    }
+}
+
+fn start_in_mode(mode: u8) -> Vec<I2cTrans> {
+    let mut transactions = start_application();
+    transactions.extend(write(vec![Register::MEAS_MODE, mode]));
+    transactions
+}
+
+#[test]
+fn can_read_blocking() {
+    let mut transactions = start_in_mode(1 << 4);
+    transactions.extend([
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+        alg_result(0x1234, 0x5678, BF::FW_MODE | BF::DATA_READY),
+    ]);
+    let mut sensor = new_awake(&transactions).start_application().ok().unwrap();
+    sensor.set_mode(MeasurementMode::ConstantPower1s).unwrap();
+    let mut delay = RecordingDelay::default();
+    let data = sensor.read_blocking(&mut delay, 1_000).unwrap();
+    assert_eq!(0x1234, data.eco2);
+    assert_eq!(0x5678, data.etvoc);
+    assert_eq!(vec![100, 100], delay.waits_ms);
+    destroy_awake(sensor);
+}
+
+#[test]
+fn read_blocking_reads_unknown_mode_from_device() {
+    let mut transactions = start_application();
+    transactions.extend([
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MEAS_MODE], vec![1 << 4]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+        alg_result(0x1234, 0x5678, BF::FW_MODE | BF::DATA_READY),
+    ]);
+    let mut sensor = new_awake(&transactions).start_application().ok().unwrap();
+    let mut delay = RecordingDelay::default();
+    let data = sensor.read_blocking(&mut delay, 1_000).unwrap();
+    assert_eq!(0x1234, data.eco2);
+    assert_eq!(vec![100], delay.waits_ms);
+    destroy_awake(sensor);
+}
+
+#[test]
+fn read_blocking_rejects_raw_data_only_mode() {
+    let transactions = start_in_mode(4 << 4);
+    let mut sensor = new_awake(&transactions).start_application().ok().unwrap();
+    sensor
+        .set_mode(MeasurementMode::ConstantPower250ms)
+        .unwrap();
+    let mut delay = RecordingDelay::default();
+    assert_error!(
+        sensor.read_blocking(&mut delay, 1_000),
+        ErrorAwake::InvalidInputData
+    );
+    destroy_awake(sensor);
+}
+
+#[test]
+fn read_blocking_times_out() {
+    let mut transactions = start_in_mode(2 << 4);
+    transactions.extend([
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+    ]);
+    let mut sensor = new_awake(&transactions).start_application().ok().unwrap();
+    sensor.set_mode(MeasurementMode::PulseHeating10s).unwrap();
+    let mut delay = RecordingDelay::default();
+    assert_error!(sensor.read_blocking(&mut delay, 2_500), ErrorAwake::Timeout);
+    assert_eq!(vec![1_000, 1_000, 500], delay.waits_ms);
+    destroy_awake(sensor);
+}
+
+#[test]
+fn read_blocking_returns_device_errors() {
+    let mut transactions = start_in_mode(1 << 4);
+    transactions.extend([
+        alg_result(0x1234, 0x5678, BF::FW_MODE),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::ALG_RESULT_DATA],
+            vec![0, 0, 0, 0, BF::FW_MODE | BF::ERROR, BF::HEATER_FAULT, 0, 0],
+        ),
+    ]);
+    let mut sensor = new_awake(&transactions).start_application().ok().unwrap();
+    sensor.set_mode(MeasurementMode::ConstantPower1s).unwrap();
+    match sensor.read_blocking(&mut RecordingDelay::default(), 1_000) {
+        Err(ErrorAwake::Device(errors)) => assert!(errors.heater_fault),
+        _ => panic!("Wrong result"),
+    }
+    destroy_awake(sensor);
+}
+
+#[test]
+fn read_blocking_in_idle_times_out_right_away() {
+    let mut transactions = start_in_mode(0);
+    transactions.push(alg_result(0x1234, 0x5678, BF::FW_MODE));
+    let mut sensor = new_awake(&transactions).start_application().ok().unwrap();
+    sensor.set_mode(MeasurementMode::Idle).unwrap();
+    let mut delay = RecordingDelay::default();
+    assert_error!(
+        sensor.read_blocking(&mut delay, 10_000),
+        ErrorAwake::Timeout
+    );
+    assert!(delay.waits_ms.is_empty());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn can_erase_blocking() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_ERASE, 0xE7, 0xA7, 0xE6, 0x09]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_ERASE]),
+    ];
+    let mut sensor = new(&transactions, wake_cycles(2));
+    let mut delay = RecordingDelay::default();
+    sensor
+        .erase_application_blocking(&mut delay, 1_000)
+        .unwrap();
+    assert_eq!(vec![500], delay.waits_ms);
+    destroy(sensor);
+}
+
+#[test]
+fn verify_blocking_times_out() {
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_VERIFY]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+    ];
+    let mut sensor = new(&transactions, wake_cycles(3));
+    let mut delay = RecordingDelay::default();
+    assert_error!(
+        sensor.verify_application_blocking(&mut delay, 100),
+        Error::Timeout
+    );
+    assert_eq!(vec![70, 30], delay.waits_ms);
+    destroy(sensor);
+}
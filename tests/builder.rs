@@ -0,0 +1,196 @@
+use embedded_ccs811::{
+    prelude::*, Ccs811Builder, Ccs811Config, Error, ErrorAwake, InitDevice, InitStep,
+    InterruptMode, MeasurementMode, SlaveAddr,
+};
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay as NoDelay,
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
+mod common;
+use crate::common::{
+    identification, software_reset, start_application, wake_cycles, write, BitFlags as BF,
+    Register, DEV_ADDR,
+};
+
+fn start_and_configure() -> Vec<I2cTrans> {
+    let mut transactions = start_application();
+    transactions.extend(write(vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]));
+    transactions.extend(write(vec![Register::MEAS_MODE, (2 << 4) | BF::INTERRUPT]));
+    transactions
+}
+
+#[test]
+fn can_build_awake_device() {
+    let mut transactions = vec![software_reset()];
+    transactions.extend(identification(BF::APP_VALID));
+    transactions.extend(start_and_configure());
+    let sensor = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .mode(MeasurementMode::PulseHeating10s)
+        .interrupt_mode(InterruptMode::OnDataReady)
+        .environment(48.25, 25.125)
+        .build(&mut NoDelay::new())
+        .ok()
+        .unwrap();
+    sensor.destroy().done();
+}
+
+#[test]
+fn can_build_device_with_wake_pin() {
+    let mut transactions = vec![software_reset()];
+    transactions.extend(identification(BF::APP_VALID));
+    transactions.extend(start_and_configure());
+    let sensor = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .with_wake_pin(wake_cycles(4), NoDelay::new())
+        .mode(MeasurementMode::PulseHeating10s)
+        .interrupt_mode(InterruptMode::OnDataReady)
+        .environment(48.25, 25.125)
+        .build(&mut NoDelay::new())
+        .ok()
+        .unwrap();
+    let (mut i2c, mut nwake, _) = sensor.destroy();
+    i2c.done();
+    nwake.done();
+}
+
+#[test]
+fn can_build_device_with_reset_pin() {
+    let mut transactions = identification(BF::APP_VALID);
+    transactions.extend(start_and_configure());
+    let nreset = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let sensor = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .with_wake_pin(wake_cycles(3), NoDelay::new())
+        .with_reset_pin(nreset)
+        .mode(MeasurementMode::PulseHeating10s)
+        .interrupt_mode(InterruptMode::OnDataReady)
+        .environment(48.25, 25.125)
+        .build(&mut NoDelay::new())
+        .ok()
+        .unwrap();
+    let (mut i2c, mut nwake, mut nreset, _) = sensor.destroy_with_reset_pin();
+    i2c.done();
+    nwake.done();
+    nreset.done();
+}
+
+#[test]
+fn does_not_write_baseline() {
+    let mut transactions = vec![software_reset()];
+    transactions.extend(identification(BF::APP_VALID));
+    transactions.extend(start_application());
+    transactions.extend(write(vec![Register::MEAS_MODE, 1 << 4]));
+    let config = Ccs811Config {
+        baseline: Some([0x34, 0x52]),
+        ..Default::default()
+    };
+    let sensor = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .config(config)
+        .build(&mut NoDelay::new())
+        .ok()
+        .unwrap();
+    assert_eq!(None, sensor.applied_config().unwrap().baseline);
+    sensor.destroy().done();
+}
+
+#[test]
+fn hands_back_interrupt_pin_with_device() {
+    let mut transactions = vec![software_reset()];
+    transactions.extend(identification(BF::APP_VALID));
+    transactions.extend(start_and_configure());
+    let (sensor, mut nint) = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .with_wake_pin(wake_cycles(4), NoDelay::new())
+        .with_interrupt_pin(PinMock::new(&[]))
+        .mode(MeasurementMode::PulseHeating10s)
+        .interrupt_mode(InterruptMode::OnDataReady)
+        .environment(48.25, 25.125)
+        .build(&mut NoDelay::new())
+        .ok()
+        .unwrap();
+    let (mut i2c, mut nwake, _) = sensor.destroy();
+    i2c.done();
+    nwake.done();
+    nint.done();
+}
+
+#[test]
+fn hands_back_interrupt_pin_on_failure() {
+    let mut transactions = vec![software_reset()];
+    transactions.extend(identification(0));
+    let (error, mut nint) = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .with_interrupt_pin(PinMock::new(&[]))
+        .build(&mut NoDelay::new())
+        .err()
+        .unwrap();
+    assert_eq!(InitStep::ValidApp, error.step);
+    match error.dev {
+        InitDevice::Boot(dev) => dev.destroy().done(),
+        _ => panic!("Wrong result"),
+    }
+    nint.done();
+}
+
+#[test]
+fn reports_unexpected_hardware_id() {
+    let transactions = [
+        software_reset(),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0x42]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_VERSION], vec![0x12]),
+    ];
+    let error = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .build(&mut NoDelay::new())
+        .err()
+        .unwrap();
+    assert_eq!(InitStep::Identify, error.step);
+    match (error.error, error.dev) {
+        (ErrorAwake::UnexpectedIdentity(0x42, (1, 2)), InitDevice::Boot(dev)) => {
+            dev.destroy().done()
+        }
+        _ => panic!("Wrong result"),
+    }
+}
+
+#[test]
+fn reports_missing_valid_app() {
+    let mut transactions = vec![software_reset()];
+    transactions.extend(identification(0));
+    let error = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .build(&mut NoDelay::new())
+        .err()
+        .unwrap();
+    assert_eq!(InitStep::ValidApp, error.step);
+    match (error.error, error.dev) {
+        (ErrorAwake::NoValidApp, InitDevice::Boot(dev)) => dev.destroy().done(),
+        _ => panic!("Wrong result"),
+    }
+}
+
+#[test]
+fn hands_back_app_device_on_configuration_failure() {
+    let mut transactions = vec![software_reset()];
+    transactions.extend(identification(BF::APP_VALID));
+    transactions.extend(start_application());
+    transactions.extend([
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::ERROR]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::ERROR_ID],
+            vec![BF::MEASMODE_INVALID],
+        ),
+    ]);
+    let error = Ccs811Builder::new(I2cMock::new(&transactions), SlaveAddr::default())
+        .with_wake_pin(wake_cycles(4), NoDelay::new())
+        .build(&mut NoDelay::new())
+        .err()
+        .unwrap();
+    assert_eq!(InitStep::Configure, error.step);
+    match (error.error, error.dev) {
+        (Error::Device(errors), InitDevice::App(dev)) => {
+            assert!(errors.invalid_measurement);
+            let (mut i2c, mut nwake, _) = dev.destroy();
+            i2c.done();
+            nwake.done();
+        }
+        _ => panic!("Wrong result"),
+    }
+}
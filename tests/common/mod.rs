@@ -1,7 +1,7 @@
-use embedded_ccs811::{mode, Ccs811, SlaveAddr};
+use embedded_ccs811::{mode, prelude::*, AlgorithmResult, Ccs811, Ccs811Awake, SlaveAddr};
 use embedded_hal_mock::eh1::{
     delay::NoopDelay as NoDelay,
-    digital::Mock as PinMock,
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
     i2c::{Mock as I2cMock, Transaction as I2cTrans},
 };
 
@@ -47,6 +47,7 @@ impl BitFlags {
     pub const THRESH: u8 = 1 << 2;
 }
 
+#[allow(unused)]
 pub fn new(
     transactions: &[I2cTrans],
     pin: PinMock,
@@ -59,12 +60,114 @@ pub fn new(
     )
 }
 
+#[allow(unused)]
 pub fn destroy<MODE>(sensor: Ccs811<I2cMock, PinMock, NoDelay, MODE>) {
     let (mut i2c, mut pin, _delay) = sensor.destroy();
     i2c.done();
     pin.done();
 }
 
+#[allow(unused)]
+pub fn new_awake(transactions: &[I2cTrans]) -> Ccs811Awake<I2cMock, mode::Boot> {
+    Ccs811Awake::new(I2cMock::new(transactions), SlaveAddr::default())
+}
+
+#[allow(unused)]
+pub fn destroy_awake<MODE>(sensor: Ccs811Awake<I2cMock, MODE>) {
+    sensor.destroy().done();
+}
+
+#[allow(unused)]
+pub fn wake_cycles(count: usize) -> PinMock {
+    let mut transactions = Vec::new();
+    for _ in 0..count {
+        transactions.push(PinTrans::set(PinState::Low));
+        transactions.push(PinTrans::set(PinState::High));
+    }
+    PinMock::new(&transactions)
+}
+
+#[allow(unused)]
+pub fn software_reset() -> I2cTrans {
+    I2cTrans::write(DEV_ADDR, vec![Register::SW_RESET, 0x11, 0xE5, 0x72, 0x8A])
+}
+
+#[allow(unused)]
+pub fn identification(status: u8) -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0x81]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_VERSION], vec![0x12]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::FW_BOOT_VERSION], vec![0x10, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::FW_APP_VERSION], vec![0x20, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![status]),
+    ]
+}
+
+#[allow(unused)]
+pub fn start_application_at(address: u8) -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(address, vec![Register::STATUS], vec![BitFlags::APP_VALID]),
+        I2cTrans::write(address, vec![Register::APP_START]),
+        I2cTrans::write_read(address, vec![Register::STATUS], vec![BitFlags::FW_MODE]),
+    ]
+}
+
+#[allow(unused)]
+pub fn start_application() -> Vec<I2cTrans> {
+    start_application_at(DEV_ADDR)
+}
+
+#[allow(unused)]
+pub fn new_awake_app(transactions: &[I2cTrans]) -> Ccs811Awake<I2cMock, mode::App> {
+    let mut all = start_application();
+    all.extend_from_slice(transactions);
+    new_awake(&all).start_application().ok().unwrap()
+}
+
+/// Register write followed by the status check in application mode.
+#[allow(unused)]
+pub fn write(data: Vec<u8>) -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write(DEV_ADDR, data),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BitFlags::FW_MODE]),
+    ]
+}
+
+/// BASELINE read followed by the status check in application mode.
+#[allow(unused)]
+pub fn read_baseline(baseline: [u8; 2]) -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::BASELINE], baseline.to_vec()),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BitFlags::FW_MODE]),
+    ]
+}
+
+/// ALG_RESULT_DATA read with zero raw data.
+#[allow(unused)]
+pub fn alg_result_at(address: u8, eco2: u16, etvoc: u16, status: u8) -> I2cTrans {
+    let eco2 = eco2.to_be_bytes();
+    let etvoc = etvoc.to_be_bytes();
+    I2cTrans::write_read(
+        address,
+        vec![Register::ALG_RESULT_DATA],
+        vec![eco2[0], eco2[1], etvoc[0], etvoc[1], status, 0, 0, 0],
+    )
+}
+
+#[allow(unused)]
+pub fn alg_result(eco2: u16, etvoc: u16, status: u8) -> I2cTrans {
+    alg_result_at(DEV_ADDR, eco2, etvoc, status)
+}
+
+#[allow(unused)]
+pub fn result(eco2: u16, etvoc: u16) -> AlgorithmResult {
+    AlgorithmResult {
+        eco2,
+        etvoc,
+        ..Default::default()
+    }
+}
+
 #[macro_export]
 macro_rules! read_status_test {
     ($name:ident, $method:ident, $expected:expr, $value: expr) => {
@@ -0,0 +1,168 @@
+use embedded_ccs811::{
+    prelude::*, Ccs811Config, Eco2Thresholds, Error, InterruptMode, MeasurementMode,
+};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::i2c::Transaction as I2cTrans;
+mod common;
+use crate::common::{
+    destroy, new, software_reset, start_application, wake_cycles, write, BitFlags as BF, Register,
+    DEV_ADDR,
+};
+
+fn apply_transactions() -> Vec<I2cTrans> {
+    let mut transactions = write(vec![Register::THRESHOLDS, 0x05, 0xDC, 0x09, 0xC4]);
+    transactions.extend(write(vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]));
+    transactions.extend(write(vec![
+        Register::MEAS_MODE,
+        (1 << 4) | BF::INTERRUPT | BF::THRESH,
+    ]));
+    transactions.extend(write(vec![Register::BASELINE, 0x34, 0x52]));
+    transactions
+}
+
+fn config() -> Ccs811Config {
+    Ccs811Config {
+        mode: MeasurementMode::ConstantPower1s,
+        interrupt_mode: InterruptMode::OnThresholdCrossed,
+        eco2_thresholds: Eco2Thresholds::new(1500, 2500),
+        environment: Some((48.25, 25.125)),
+        baseline: Some([0x34, 0x52]),
+    }
+}
+
+#[test]
+fn applies_config_in_one_wake_cycle() {
+    let mut transactions = start_application();
+    transactions.extend(apply_transactions());
+    let mut sensor = new(&transactions, wake_cycles(2))
+        .start_application()
+        .ok()
+        .unwrap();
+    assert_eq!(None, sensor.applied_config());
+    sensor.apply(&config()).unwrap();
+    assert_eq!(Some(config()), sensor.applied_config());
+    destroy(sensor);
+}
+
+#[test]
+fn setters_update_applied_config() {
+    let mut transactions = start_application();
+    transactions.extend(apply_transactions());
+    transactions.extend(write(vec![
+        Register::MEAS_MODE,
+        (2 << 4) | BF::INTERRUPT | BF::THRESH,
+    ]));
+    transactions.extend(write(vec![Register::BASELINE, 0x12, 0x34]));
+    let mut sensor = new(&transactions, wake_cycles(4))
+        .start_application()
+        .ok()
+        .unwrap();
+    sensor.apply(&config()).unwrap();
+    sensor.set_mode(MeasurementMode::PulseHeating10s).unwrap();
+    sensor.set_baseline([0x12, 0x34]).unwrap();
+    let expected = Ccs811Config {
+        mode: MeasurementMode::PulseHeating10s,
+        baseline: Some([0x12, 0x34]),
+        ..config()
+    };
+    assert_eq!(Some(expected), sensor.applied_config());
+    destroy(sensor);
+}
+
+#[test]
+fn first_setter_creates_config_record() {
+    let mut transactions = start_application();
+    transactions.extend(write(vec![Register::MEAS_MODE, BF::INTERRUPT]));
+    transactions.extend(write(vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]));
+    let mut sensor = new(&transactions, wake_cycles(3))
+        .start_application()
+        .ok()
+        .unwrap();
+    sensor
+        .set_interrupt_mode(InterruptMode::OnDataReady)
+        .unwrap();
+    sensor.set_environment(48.25, 25.125).unwrap();
+    let expected = Ccs811Config {
+        mode: MeasurementMode::Idle,
+        interrupt_mode: InterruptMode::OnDataReady,
+        eco2_thresholds: None,
+        environment: Some((48.25, 25.125)),
+        baseline: None,
+    };
+    assert_eq!(Some(expected), sensor.applied_config());
+    destroy(sensor);
+}
+
+#[test]
+fn failed_apply_keeps_previous_config() {
+    let mut transactions = start_application();
+    transactions.extend(apply_transactions());
+    transactions.extend(write(vec![Register::THRESHOLDS, 0x03, 0xE8, 0x07, 0xD0]));
+    transactions.extend(write(vec![Register::ENV_DATA, 0x64, 0x00, 0x64, 0x00]));
+    transactions.push(
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 2 << 4]).with_error(ErrorKind::Bus),
+    );
+    let mut sensor = new(&transactions, wake_cycles(3))
+        .start_application()
+        .ok()
+        .unwrap();
+    sensor.apply(&config()).unwrap();
+    let new_config = Ccs811Config {
+        mode: MeasurementMode::PulseHeating10s,
+        interrupt_mode: InterruptMode::Disabled,
+        eco2_thresholds: Eco2Thresholds::new(1000, 2000),
+        environment: Some((50.0, 25.0)),
+        baseline: None,
+    };
+    assert!(sensor.apply(&new_config).is_err());
+    assert_eq!(Some(config()), sensor.applied_config());
+    destroy(sensor);
+}
+
+#[test]
+fn can_reapply_config_after_reset() {
+    let mut transactions = start_application();
+    transactions.extend(apply_transactions());
+    transactions.push(software_reset());
+    transactions.extend(start_application());
+    transactions.extend(apply_transactions());
+    let mut sensor = new(&transactions, wake_cycles(5))
+        .start_application()
+        .ok()
+        .unwrap();
+    sensor.apply(&config()).unwrap();
+    let sensor = sensor.software_reset().ok().unwrap();
+    let mut sensor = sensor.start_application().ok().unwrap();
+    assert_eq!(Some(config()), sensor.applied_config());
+    sensor.reapply().unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn reapply_without_config_does_nothing() {
+    let mut sensor = new(&start_application(), wake_cycles(2))
+        .start_application()
+        .ok()
+        .unwrap();
+    sensor.reapply().unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn invalid_environment_is_rejected_before_writing() {
+    let mut transactions = start_application();
+    transactions.extend(apply_transactions());
+    let mut sensor = new(&transactions, wake_cycles(3))
+        .start_application()
+        .ok()
+        .unwrap();
+    sensor.apply(&config()).unwrap();
+    let invalid = Ccs811Config {
+        eco2_thresholds: Eco2Thresholds::new(1000, 2000),
+        environment: Some((101.0, 25.0)),
+        ..config()
+    };
+    assert_error!(sensor.apply(&invalid), Error::InvalidInputData);
+    assert_eq!(Some(config()), sensor.applied_config());
+    destroy(sensor);
+}
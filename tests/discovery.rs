@@ -0,0 +1,83 @@
+use embedded_ccs811::{
+    prelude::*, scan, DiscoveredDevice, FirmwareMode, MeasurementMode, SlaveAddr,
+};
+use embedded_hal::i2c::{ErrorKind, NoAcknowledgeSource};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+mod common;
+use crate::common::{BitFlags as BF, Register, DEV_ADDR};
+
+const ALT_ADDR: u8 = 0x5B;
+
+fn no_ack(address: u8) -> I2cTrans {
+    I2cTrans::write_read(address, vec![Register::HW_ID], vec![0])
+        .with_error(ErrorKind::NoAcknowledge(NoAcknowledgeSource::Address))
+}
+
+#[test]
+fn finds_nothing_on_empty_bus() {
+    let mut i2c = I2cMock::new(&[no_ack(DEV_ADDR), no_ack(ALT_ADDR)]);
+    assert_eq!([None, None], scan(&mut i2c).unwrap());
+    i2c.done();
+}
+
+#[test]
+fn finds_sensors_in_boot_and_app_mode() {
+    let mut i2c = I2cMock::new(&[
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0x81]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::HW_ID], vec![0x81]),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::MEAS_MODE], vec![BF::INTERRUPT]),
+    ]);
+    let found = scan(&mut i2c).unwrap();
+    let first = found[0].unwrap();
+    assert_eq!(SlaveAddr::Alternative(false), first.address);
+    assert_eq!(FirmwareMode::Boot, first.firmware_mode);
+    let second = found[1].unwrap();
+    assert_eq!(SlaveAddr::Alternative(true), second.address);
+    assert_eq!(FirmwareMode::Application, second.firmware_mode);
+    i2c.done();
+}
+
+#[test]
+fn ignores_other_devices() {
+    let mut i2c = I2cMock::new(&[
+        no_ack(DEV_ADDR),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::HW_ID], vec![0x42]),
+    ]);
+    assert_eq!([None, None], scan(&mut i2c).unwrap());
+    i2c.done();
+}
+
+#[test]
+fn reports_bus_errors() {
+    let mut i2c = I2cMock::new(&[
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0]).with_error(ErrorKind::Bus),
+    ]);
+    assert_eq!(ErrorKind::Bus, scan(&mut i2c).unwrap_err());
+    i2c.done();
+}
+
+#[test]
+fn discovered_app_device_keeps_interrupt_configuration() {
+    let mut i2c = I2cMock::new(&[
+        no_ack(DEV_ADDR),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::HW_ID], vec![0x81]),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::MEAS_MODE], vec![BF::INTERRUPT]),
+        // driver
+        I2cTrans::write(
+            ALT_ADDR,
+            vec![Register::MEAS_MODE, (1 << 4) | BF::INTERRUPT],
+        ),
+        I2cTrans::write_read(ALT_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ]);
+    let found = scan(&mut i2c).unwrap();
+    match found[1].unwrap().into_device(&mut i2c) {
+        DiscoveredDevice::App(mut sensor) => {
+            sensor.set_mode(MeasurementMode::ConstantPower1s).unwrap();
+        }
+        DiscoveredDevice::Boot(_) => panic!("Wrong mode"),
+    }
+    i2c.done();
+}
@@ -0,0 +1,109 @@
+use embedded_ccs811::{
+    drive, mode, AlgorithmResult, Ccs811Awake, Ccs811Drive, ErrorAwake, MeasurementMode,
+};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+mod common;
+use crate::common::{destroy_awake, new_awake_app, write, BitFlags as BF, Register, DEV_ADDR};
+
+fn set_mode(value: u8) -> Vec<I2cTrans> {
+    write(vec![Register::MEAS_MODE, value])
+}
+
+fn new_idle(
+    transactions: &[I2cTrans],
+) -> Ccs811Drive<Ccs811Awake<I2cMock, mode::App>, drive::Idle> {
+    let mut all = set_mode(0);
+    all.extend_from_slice(transactions);
+    let sensor = new_awake_app(&all);
+    Ccs811Drive::new(sensor).ok().unwrap()
+}
+
+#[test]
+fn can_create_idle() {
+    let sensor = new_idle(&[]);
+    assert_eq!(MeasurementMode::Idle, sensor.mode());
+    destroy_awake(sensor.into_inner());
+}
+
+#[test]
+fn can_read_algorithm_data_in_mode_1() {
+    let mut transactions = set_mode(1 << 4);
+    transactions.push(I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::ALG_RESULT_DATA],
+        vec![
+            0x12,
+            0x34,
+            0x56,
+            0x78,
+            BF::DATA_READY | BF::FW_MODE,
+            0,
+            0x8A,
+            0xBC,
+        ],
+    ));
+    let sensor = new_idle(&transactions);
+    let mut sensor = sensor.into_mode::<drive::ConstantPower1s>().ok().unwrap();
+    assert_eq!(MeasurementMode::ConstantPower1s, sensor.mode());
+    let data = sensor.data().unwrap();
+    assert_eq!(
+        AlgorithmResult {
+            eco2: 0x1234,
+            etvoc: 0x5678,
+            raw_current: 0xBC >> 2,
+            raw_voltage: 0x08A,
+        },
+        data
+    );
+    destroy_awake(sensor.into_inner());
+}
+
+#[test]
+fn can_read_raw_data_in_mode_4() {
+    let mut transactions = set_mode(4 << 4);
+    transactions.extend([
+        I2cTrans::write_read(DEV_ADDR, vec![Register::RAW_DATA], vec![0x8A, 0xBC]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ]);
+    let sensor = new_idle(&transactions);
+    let mut sensor = sensor
+        .into_mode::<drive::ConstantPower250ms>()
+        .ok()
+        .unwrap();
+    assert_eq!((0xBC >> 2, 0x08A), sensor.raw_data().unwrap());
+    destroy_awake(sensor.into_inner());
+}
+
+#[test]
+fn can_go_back_to_idle() {
+    let mut transactions = set_mode(2 << 4);
+    transactions.extend(set_mode(0));
+    let sensor = new_idle(&transactions);
+    let sensor = sensor.into_mode::<drive::PulseHeating10s>().ok().unwrap();
+    let sensor = sensor.into_mode::<drive::Idle>().ok().unwrap();
+    destroy_awake(sensor.into_inner());
+}
+
+#[test]
+fn returns_device_in_previous_mode_on_error() {
+    let transactions = [
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 3 << 4]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::ERROR]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::ERROR_ID],
+            vec![BF::MEASMODE_INVALID],
+        ),
+    ];
+    let sensor = new_idle(&transactions);
+    let error = sensor
+        .into_mode::<drive::LowPowerPulseHeating60s>()
+        .err()
+        .unwrap();
+    match error.error {
+        ErrorAwake::Device(errors) => assert!(errors.invalid_measurement),
+        _ => panic!("Wrong error"),
+    }
+    assert_eq!(MeasurementMode::Idle, error.dev.mode());
+    destroy_awake(error.dev.into_inner());
+}
@@ -0,0 +1,95 @@
+use embedded_ccs811::{mode, prelude::*, Ccs811, Error, SlaveAddr};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay as NoDelay,
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+    MockError,
+};
+use std::io::ErrorKind as IoErrorKind;
+mod common;
+use crate::common::{BitFlags as BF, Register, DEV_ADDR};
+
+fn new_with_reset(
+    transactions: &[I2cTrans],
+    nwake: PinMock,
+    nreset: PinMock,
+) -> Ccs811<I2cMock, PinMock, NoDelay, mode::Boot, PinMock> {
+    Ccs811::new_with_reset_pin(
+        I2cMock::new(transactions),
+        SlaveAddr::default(),
+        nwake,
+        nreset,
+        NoDelay::new(),
+    )
+}
+
+fn destroy_with_reset<MODE>(sensor: Ccs811<I2cMock, PinMock, NoDelay, MODE, PinMock>) {
+    let (mut i2c, mut nwake, mut nreset, _delay) = sensor.destroy_with_reset_pin();
+    i2c.done();
+    nwake.done();
+    nreset.done();
+}
+
+fn reset_pulse() -> PinMock {
+    PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)])
+}
+
+#[test]
+fn can_do_hardware_reset_in_boot_mode() {
+    let sensor = new_with_reset(&[], PinMock::new(&[]), reset_pulse());
+    let sensor = sensor.hardware_reset().ok().unwrap();
+    destroy_with_reset(sensor);
+}
+
+#[test]
+fn can_do_hardware_reset_in_app_mode() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // reset
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+    ];
+    let sensor = new_with_reset(&transactions, nwake, reset_pulse());
+    let sensor = sensor.start_application().ok().unwrap();
+    let mut sensor = sensor.hardware_reset().ok().unwrap();
+    assert!(sensor.has_valid_app().unwrap());
+    destroy_with_reset(sensor);
+}
+
+#[test]
+fn can_fall_back_to_hardware_reset_on_mode_change_failure() {
+    let nwake = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0])
+            .with_error(ErrorKind::Other),
+    ];
+    let sensor = new_with_reset(&transactions, nwake, reset_pulse());
+    let result = sensor.start_application().err().unwrap();
+    match result.error {
+        Error::I2C(_) => (),
+        _ => panic!("Wrong error"),
+    }
+    let sensor = result.dev.hardware_reset().ok().unwrap();
+    destroy_with_reset(sensor);
+}
+
+#[test]
+fn returns_device_on_reset_pin_error() {
+    let nreset =
+        PinMock::new(&[PinTrans::set(PinState::Low).with_error(MockError::Io(IoErrorKind::Other))]);
+    let sensor = new_with_reset(&[], PinMock::new(&[]), nreset);
+    let result = sensor.hardware_reset().err().unwrap();
+    match result.error {
+        Error::Pin(_) => (),
+        _ => panic!("Wrong error"),
+    }
+    destroy_with_reset(result.dev);
+}
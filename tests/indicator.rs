@@ -0,0 +1,132 @@
+use embedded_ccs811::{DeviceErrors, Eco2Band, Eco2Thresholds, Indicator, IndicatorConfig};
+use embedded_hal_mock::eh1::digital::{
+    Mock as PinMock, State as PinState, Transaction as PinTrans,
+};
+mod common;
+use crate::common::result;
+
+fn pin(states: &[PinState]) -> PinMock {
+    let transactions: Vec<_> = states.iter().map(|s| PinTrans::set(*s)).collect();
+    PinMock::new(&transactions)
+}
+
+fn done<BZ>(indicator: Indicator<PinMock, PinMock, PinMock, BZ>) -> BZ {
+    let (mut red, mut green, mut blue, buzzer) = indicator.destroy();
+    red.done();
+    green.done();
+    blue.done();
+    buzzer
+}
+
+use PinState::{High, Low};
+
+#[test]
+fn shows_bands() {
+    let red = pin(&[Low, High]);
+    let green = pin(&[High, Low]);
+    let blue = pin(&[Low]);
+    let buzzer = pin(&[Low, High]);
+    let mut indicator = Indicator::new(
+        red,
+        green,
+        blue,
+        Eco2Thresholds::default(),
+        IndicatorConfig::default(),
+    )
+    .with_buzzer(buzzer);
+    indicator.update(&result(1000, 0), 0).unwrap();
+    assert_eq!(Some(Eco2Band::Low), indicator.band());
+    // unchanged outputs are not written
+    indicator.update(&result(1100, 0), 1000).unwrap();
+    indicator.tick(1100).unwrap();
+    indicator.update(&result(1600, 0), 2000).unwrap();
+    assert_eq!(Some(Eco2Band::Medium), indicator.band());
+    indicator.update(&result(2600, 0), 3000).unwrap();
+    assert_eq!(Some(Eco2Band::High), indicator.band());
+    done(indicator).done();
+}
+
+#[test]
+fn follows_device_hysteresis() {
+    let red = pin(&[Low]);
+    let green = pin(&[High]);
+    let blue = pin(&[Low]);
+    let mut indicator = Indicator::new(
+        red,
+        green,
+        blue,
+        Eco2Thresholds::default(),
+        IndicatorConfig::default(),
+    );
+    indicator.update(&result(1000, 0), 0).unwrap();
+    indicator.update(&result(1540, 0), 1000).unwrap();
+    assert_eq!(Some(Eco2Band::Low), indicator.band());
+    done(indicator);
+}
+
+#[test]
+fn can_show_software_band() {
+    let red = pin(&[High]);
+    let green = pin(&[Low]);
+    let blue = pin(&[Low]);
+    let mut indicator = Indicator::new(
+        red,
+        green,
+        blue,
+        Eco2Thresholds::default(),
+        IndicatorConfig::default(),
+    );
+    indicator.set_band(Eco2Band::High, 0).unwrap();
+    done(indicator);
+}
+
+#[test]
+fn blinks_on_faults() {
+    let red = pin(&[Low]);
+    let green = pin(&[High, Low, High]);
+    // 2 blinks: on, off, on, off, then a pause of 4 blinks
+    let blue = pin(&[Low, High, Low, High, Low, High, Low]);
+    let buzzer = pin(&[Low, High, Low, High, Low, High, Low]);
+    let config = IndicatorConfig {
+        blink_ms: 100,
+        buzz_on_fault: true,
+        ..Default::default()
+    };
+    let mut indicator =
+        Indicator::new(red, green, blue, Eco2Thresholds::default(), config).with_buzzer(buzzer);
+    indicator.update(&result(500, 0), 0).unwrap();
+    let errors = DeviceErrors {
+        max_resistance: true,
+        ..Default::default()
+    };
+    indicator.on_device_errors(&errors, 1000).unwrap();
+    assert!(indicator.has_fault());
+    // the same fault does not restart the pattern
+    indicator.on_device_errors(&errors, 1050).unwrap();
+    for now_ms in [1100, 1200, 1300, 1400, 1500, 1700, 1800] {
+        indicator.tick(now_ms).unwrap();
+    }
+    indicator.update(&result(500, 0), 1850).unwrap();
+    assert!(!indicator.has_fault());
+    done(indicator).done();
+}
+
+#[test]
+fn long_blinks_do_not_overflow() {
+    let red = pin(&[Low]);
+    let green = pin(&[High, Low]);
+    let blue = pin(&[Low, High]);
+    let config = IndicatorConfig {
+        blink_ms: u32::MAX,
+        ..Default::default()
+    };
+    let mut indicator = Indicator::new(red, green, blue, Eco2Thresholds::default(), config);
+    indicator.update(&result(500, 0), 0).unwrap();
+    let errors = DeviceErrors {
+        heater_fault: true,
+        ..Default::default()
+    };
+    indicator.on_device_errors(&errors, 1000).unwrap();
+    indicator.tick(u32::MAX).unwrap();
+    done(indicator);
+}
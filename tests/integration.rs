@@ -1,7 +1,9 @@
-use embedded_ccs811::{prelude::*, FirmwareMode as FwMode};
+use embedded_ccs811::{
+    prelude::*, Ccs811Awake, DeviceInfo, Error, ErrorAwake, FirmwareMode as FwMode, SlaveAddr,
+};
 use embedded_hal_mock::eh1::{
     digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
-    i2c::Transaction as I2cTrans,
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
 };
 mod common;
 use crate::common::{destroy, new, BitFlags as BF, Register, DEV_ADDR};
@@ -57,3 +59,86 @@ read_status_test!(can_get_invalid_app, has_valid_app, false, 0);
 read_status_test!(can_get_valid_app, has_valid_app, true, BF::APP_VALID);
 read_status_test!(fw_mode_boot, firmware_mode, FwMode::Boot, 0);
 read_status_test!(fw_mode_app, firmware_mode, FwMode::Application, BF::FW_MODE);
+
+fn identification(hw_id: u8, hw_version: u8) -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![hw_id]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_VERSION], vec![hw_version]),
+    ]
+}
+
+fn firmware_info() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::FW_BOOT_VERSION], vec![0x10, 0x00]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::FW_APP_VERSION], vec![0x20, 0x01]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::STATUS],
+            vec![BF::APP_VALID | BF::FW_MODE],
+        ),
+    ]
+}
+
+#[test]
+fn can_identify() {
+    let nwake = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let mut transactions = identification(0x81, 0x12);
+    transactions.extend(firmware_info());
+    let mut sensor = new(&transactions, nwake);
+    let expected = DeviceInfo {
+        hardware_id: 0x81,
+        hardware_version: (1, 2),
+        bootloader_version: (1, 0, 0),
+        application_version: (2, 0, 1),
+        has_valid_app: true,
+        firmware_mode: FwMode::Application,
+    };
+    assert_eq!(expected, sensor.identify().unwrap());
+    destroy(sensor);
+}
+
+macro_rules! unexpected_identity_test {
+    ($name:ident, $hw_id:expr, $hw_version:expr, $expected_version:expr) => {
+        #[test]
+        fn $name() {
+            let nwake =
+                PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+            let mut sensor = new(&identification($hw_id, $hw_version), nwake);
+            match sensor.identify() {
+                Err(Error::UnexpectedIdentity($hw_id, version)) => {
+                    assert_eq!($expected_version, version)
+                }
+                _ => panic!("Wrong result"),
+            }
+            destroy(sensor);
+        }
+    };
+}
+
+unexpected_identity_test!(cannot_identify_wrong_hw_id, 0x42, 0x12, (1, 2));
+unexpected_identity_test!(cannot_identify_wrong_hw_version, 0x81, 0x22, (2, 2));
+
+#[test]
+fn can_create_identified() {
+    let mut transactions = identification(0x81, 0x12);
+    transactions.extend(firmware_info());
+    let i2c = I2cMock::new(&transactions);
+    let (sensor, info) = Ccs811Awake::new_identified(i2c, SlaveAddr::default())
+        .ok()
+        .unwrap();
+    assert_eq!(0x81, info.hardware_id);
+    sensor.destroy().done();
+}
+
+#[test]
+fn returns_device_if_not_identified() {
+    let i2c = I2cMock::new(&identification(0x42, 0x12));
+    let result = Ccs811Awake::new_identified(i2c, SlaveAddr::default())
+        .err()
+        .unwrap();
+    match result.error {
+        ErrorAwake::UnexpectedIdentity(0x42, (1, 2)) => (),
+        _ => panic!("Wrong error"),
+    }
+    result.dev.destroy().done();
+}
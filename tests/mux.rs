@@ -0,0 +1,93 @@
+use embedded_ccs811::{prelude::*, Ccs811Awake, ErrorAwake, MuxSensors, SlaveAddr, Tca9548a};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+mod common;
+use crate::common::{alg_result, result, start_application, BitFlags as BF, Register, DEV_ADDR};
+
+const MUX_ADDR: u8 = 0x70;
+
+fn select(channel: u8) -> I2cTrans {
+    I2cTrans::write(MUX_ADDR, vec![1 << channel])
+}
+
+/// Select the channel before each transaction.
+fn on_channel(channel: u8, transactions: Vec<I2cTrans>) -> Vec<I2cTrans> {
+    transactions
+        .into_iter()
+        .flat_map(|transaction| [select(channel), transaction])
+        .collect()
+}
+
+#[test]
+fn selects_channel_before_each_transaction() {
+    let mux = Tca9548a::new(
+        I2cMock::new(&[
+            select(3),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0x81]),
+            select(3),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        ]),
+        MUX_ADDR,
+    );
+    let mut sensor = Ccs811Awake::new(mux.channel(3).unwrap(), SlaveAddr::default());
+    assert_eq!(0x81, sensor.hardware_id().unwrap());
+    sensor.destroy();
+    mux.destroy().done();
+}
+
+#[test]
+fn rejects_invalid_channel() {
+    let mux = Tca9548a::new(I2cMock::new(&[]), MUX_ADDR);
+    assert!(mux.channel(8).is_none());
+    mux.destroy().done();
+}
+
+#[test]
+fn collects_data_of_all_sensors() {
+    let mut transactions = on_channel(0, start_application());
+    transactions.extend(on_channel(2, start_application()));
+    transactions.extend(on_channel(
+        0,
+        vec![alg_result(0x20, 1, BF::FW_MODE | BF::DATA_READY)],
+    ));
+    transactions.extend(on_channel(2, vec![alg_result(0, 0, BF::FW_MODE)]));
+    let mux = Tca9548a::new(I2cMock::new(&transactions), MUX_ADDR);
+    let sensors = MuxSensors::new(&mux, 0b0000_0101, SlaveAddr::default());
+    let (mut sensors, errors) = sensors.start_application();
+    assert!(errors.iter().all(Option::is_none));
+    let data = sensors.data();
+    match data {
+        [Some(Ok(first)), None, Some(Err(nb::Error::WouldBlock)), None, None, None, None, None] => {
+            assert_eq!(result(0x20, 1), first)
+        }
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(0b0000_0101, sensors.destroy());
+    mux.destroy().done();
+}
+
+#[test]
+fn returns_errors_of_sensors_which_did_not_start() {
+    let mut transactions = on_channel(0, start_application());
+    transactions.extend(on_channel(
+        1,
+        vec![I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::STATUS],
+            vec![0],
+        )],
+    ));
+    let mux = Tca9548a::new(I2cMock::new(&transactions), MUX_ADDR);
+    let sensors = MuxSensors::new(&mux, 0b0000_0011, SlaveAddr::default());
+    let (mut sensors, errors) = sensors.start_application();
+    assert!(sensors.sensor(0).is_some());
+    assert!(sensors.sensor(1).is_none());
+    match &errors[1] {
+        Some(e) => match e.error {
+            ErrorAwake::NoValidApp => (),
+            _ => panic!("Wrong error"),
+        },
+        None => panic!("Missing error"),
+    }
+    assert_eq!(0b0000_0001, sensors.destroy());
+    mux.destroy().done();
+}
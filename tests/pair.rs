@@ -0,0 +1,288 @@
+use core::cell::RefCell;
+use critical_section::Mutex;
+use embedded_ccs811::{
+    mode, Ccs811, Ccs811Awake, Ccs811Pair, ErrorAwake, MeasurementMode, PairError, SlaveAddr,
+};
+use embedded_hal::{
+    digital::{self, OutputPin},
+    i2c::{self, I2c, Operation},
+};
+use embedded_hal_bus::i2c::{CriticalSectionDevice, RefCellDevice};
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay as NoDelay,
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
+mod common;
+use crate::common::{
+    alg_result_at, result, start_application_at, BitFlags as BF, Register, DEV_ADDR,
+};
+
+const FIRST: u8 = DEV_ADDR;
+const SECOND: u8 = 0x5B;
+
+fn all_transactions(transactions: &[I2cTrans]) -> Vec<I2cTrans> {
+    let mut all = start_application_at(FIRST);
+    all.extend(start_application_at(SECOND));
+    all.extend_from_slice(transactions);
+    all
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Event {
+    Wake(u8),
+    Sleep(u8),
+    Transfer(u8),
+}
+
+/// nWAKE pin recording its changes for the device at the given address.
+struct NWakePin<'a> {
+    address: u8,
+    events: &'a RefCell<Vec<Event>>,
+}
+
+impl digital::ErrorType for NWakePin<'_> {
+    type Error = core::convert::Infallible;
+}
+
+impl OutputPin for NWakePin<'_> {
+    fn set_low(&mut self) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push(Event::Wake(self.address));
+        Ok(())
+    }
+
+    fn set_high(&mut self) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push(Event::Sleep(self.address));
+        Ok(())
+    }
+}
+
+/// Bus handle recording the address of each transaction.
+struct RecordingI2c<'a, I2C> {
+    i2c: I2C,
+    events: &'a RefCell<Vec<Event>>,
+}
+
+impl<I2C: I2c> i2c::ErrorType for RecordingI2c<'_, I2C> {
+    type Error = I2C::Error;
+}
+
+impl<I2C: I2c> I2c for RecordingI2c<'_, I2C> {
+    fn read(&mut self, address: u8, read: &mut [u8]) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push(Event::Transfer(address));
+        self.i2c.read(address, read)
+    }
+
+    fn write(&mut self, address: u8, write: &[u8]) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push(Event::Transfer(address));
+        self.i2c.write(address, write)
+    }
+
+    fn write_read(
+        &mut self,
+        address: u8,
+        write: &[u8],
+        read: &mut [u8],
+    ) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push(Event::Transfer(address));
+        self.i2c.write_read(address, write, read)
+    }
+
+    fn transaction(
+        &mut self,
+        address: u8,
+        operations: &mut [Operation<'_>],
+    ) -> Result<(), Self::Error> {
+        self.events.borrow_mut().push(Event::Transfer(address));
+        self.i2c.transaction(address, operations)
+    }
+}
+
+/// Check that each device is only awake around its own transactions.
+fn assert_wakes_only_around_own_transfers(events: &[Event]) {
+    let mut awake = None;
+    for event in events {
+        match *event {
+            Event::Wake(address) => {
+                assert_eq!(None, awake);
+                awake = Some(address);
+            }
+            Event::Sleep(address) => {
+                assert_eq!(Some(address), awake);
+                awake = None;
+            }
+            Event::Transfer(address) => assert_eq!(Some(address), awake),
+        }
+    }
+    assert_eq!(None, awake);
+}
+
+#[test]
+fn can_read_data_on_refcell_bus() {
+    let bus = RefCell::new(I2cMock::new(&all_transactions(&[
+        alg_result_at(FIRST, 0x20, 1, BF::FW_MODE | BF::DATA_READY),
+        alg_result_at(SECOND, 0x30, 1, BF::FW_MODE | BF::DATA_READY),
+    ])));
+    let first = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(false));
+    let second = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(true));
+    let mut pair = Ccs811Pair::new(first, second)
+        .start_application()
+        .ok()
+        .unwrap();
+    assert_eq!((result(0x20, 1), result(0x30, 1)), pair.data().unwrap());
+    pair.destroy();
+    bus.into_inner().done();
+}
+
+#[test]
+fn keeps_data_until_both_are_ready() {
+    let bus = Mutex::new(RefCell::new(I2cMock::new(&all_transactions(&[
+        alg_result_at(FIRST, 0x20, 1, BF::FW_MODE | BF::DATA_READY),
+        alg_result_at(SECOND, 0x30, 1, BF::FW_MODE),
+        alg_result_at(SECOND, 0x31, 1, BF::FW_MODE | BF::DATA_READY),
+    ]))));
+    let first = Ccs811Awake::new(
+        CriticalSectionDevice::new(&bus),
+        SlaveAddr::Alternative(false),
+    );
+    let second = Ccs811Awake::new(
+        CriticalSectionDevice::new(&bus),
+        SlaveAddr::Alternative(true),
+    );
+    let mut pair = Ccs811Pair::new(first, second)
+        .start_application()
+        .ok()
+        .unwrap();
+    match pair.data() {
+        Err(nb::Error::WouldBlock) => (),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!((result(0x20, 1), result(0x31, 1)), pair.data().unwrap());
+    pair.destroy();
+    bus.into_inner().into_inner().done();
+}
+
+#[test]
+fn reports_errors_per_device() {
+    let bus = RefCell::new(I2cMock::new(&all_transactions(&[
+        alg_result_at(FIRST, 0x20, 1, BF::FW_MODE | BF::DATA_READY),
+        I2cTrans::write_read(
+            SECOND,
+            vec![Register::ALG_RESULT_DATA],
+            vec![0, 0, 0, 0, BF::FW_MODE | BF::ERROR, BF::HEATER_FAULT, 0, 0],
+        ),
+        alg_result_at(SECOND, 0x30, 1, BF::FW_MODE | BF::DATA_READY),
+    ])));
+    let first = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(false));
+    let second = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(true));
+    let mut pair = Ccs811Pair::new(first, second)
+        .start_application()
+        .ok()
+        .unwrap();
+    match pair.data() {
+        Err(nb::Error::Other(PairError::Second(ErrorAwake::Device(errors)))) => {
+            assert!(errors.heater_fault)
+        }
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!((result(0x20, 1), result(0x30, 1)), pair.data().unwrap());
+    pair.destroy();
+    bus.into_inner().done();
+}
+
+#[test]
+fn configures_both_devices_even_if_one_fails() {
+    let bus = RefCell::new(I2cMock::new(&all_transactions(&[
+        I2cTrans::write(FIRST, vec![Register::MEAS_MODE, 1 << 4]),
+        I2cTrans::write_read(FIRST, vec![Register::STATUS], vec![BF::FW_MODE | BF::ERROR]),
+        I2cTrans::write_read(FIRST, vec![Register::ERROR_ID], vec![BF::MEASMODE_INVALID]),
+        I2cTrans::write(SECOND, vec![Register::MEAS_MODE, 1 << 4]),
+        I2cTrans::write_read(SECOND, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ])));
+    let first = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(false));
+    let second = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(true));
+    let mut pair = Ccs811Pair::new(first, second)
+        .start_application()
+        .ok()
+        .unwrap();
+    match pair.set_mode(MeasurementMode::ConstantPower1s) {
+        Err(PairError::First(ErrorAwake::Device(errors))) => assert!(errors.invalid_measurement),
+        _ => panic!("Wrong result"),
+    }
+    pair.destroy();
+    bus.into_inner().done();
+}
+
+#[test]
+fn returns_both_devices_if_start_fails() {
+    let mut transactions = start_application_at(FIRST);
+    transactions.push(I2cTrans::write_read(
+        SECOND,
+        vec![Register::STATUS],
+        vec![0],
+    ));
+    let bus = RefCell::new(I2cMock::new(&transactions));
+    let first = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(false));
+    let second = Ccs811Awake::new(RefCellDevice::new(&bus), SlaveAddr::Alternative(true));
+    match Ccs811Pair::new(first, second).start_application() {
+        Err((Ok(first), Err(error))) => {
+            let _: Ccs811Awake<_, mode::App> = first;
+            match error.error {
+                ErrorAwake::NoValidApp => (),
+                _ => panic!("Wrong error"),
+            }
+        }
+        _ => panic!("Wrong result"),
+    }
+    bus.into_inner().done();
+}
+
+#[test]
+fn wakes_each_device_through_its_own_pin() {
+    let bus = RefCell::new(I2cMock::new(&all_transactions(&[
+        I2cTrans::write(FIRST, vec![Register::MEAS_MODE, 1 << 4]),
+        I2cTrans::write_read(FIRST, vec![Register::STATUS], vec![BF::FW_MODE]),
+        I2cTrans::write(SECOND, vec![Register::MEAS_MODE, 1 << 4]),
+        I2cTrans::write_read(SECOND, vec![Register::STATUS], vec![BF::FW_MODE]),
+        alg_result_at(FIRST, 0x20, 1, BF::FW_MODE | BF::DATA_READY),
+        alg_result_at(SECOND, 0x30, 1, BF::FW_MODE | BF::DATA_READY),
+    ])));
+    let events = RefCell::new(Vec::new());
+    let first = Ccs811::new(
+        RecordingI2c {
+            i2c: RefCellDevice::new(&bus),
+            events: &events,
+        },
+        SlaveAddr::Alternative(false),
+        NWakePin {
+            address: FIRST,
+            events: &events,
+        },
+        NoDelay::new(),
+    );
+    let second = Ccs811::new(
+        RecordingI2c {
+            i2c: RefCellDevice::new(&bus),
+            events: &events,
+        },
+        SlaveAddr::Alternative(true),
+        NWakePin {
+            address: SECOND,
+            events: &events,
+        },
+        NoDelay::new(),
+    );
+    let mut pair = Ccs811Pair::new(first, second)
+        .start_application()
+        .ok()
+        .unwrap();
+    pair.set_mode(MeasurementMode::ConstantPower1s).unwrap();
+    assert_eq!((result(0x20, 1), result(0x30, 1)), pair.data().unwrap());
+    pair.destroy();
+    bus.into_inner().done();
+    let events = events.into_inner();
+    assert_wakes_only_around_own_transfers(&events);
+    for address in [FIRST, SECOND] {
+        let wakes = events.iter().filter(|e| **e == Event::Wake(address));
+        assert_eq!(3, wakes.count());
+    }
+}
@@ -0,0 +1,179 @@
+use embedded_ccs811::{prelude::*, ErrorAwake, MeasurementMode};
+use embedded_hal::i2c::ErrorKind;
+use embedded_hal_mock::eh1::{delay::NoopDelay as NoDelay, i2c::Transaction as I2cTrans};
+mod common;
+use crate::common::{destroy_awake, new_awake_app, write, BitFlags as BF, Register, DEV_ADDR};
+
+fn self_test_transactions(
+    hw_id: u8,
+    status: u8,
+    raw_data: Option<[u8; 2]>,
+    error_id: u8,
+    baseline_readback: [u8; 2],
+) -> Vec<I2cTrans> {
+    let mut transactions = vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::MEAS_MODE], vec![0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![hw_id]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_VERSION], vec![0x12]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![status]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+    ];
+    match raw_data {
+        Some(raw_data) => {
+            transactions.push(I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::STATUS],
+                vec![status],
+            ));
+            transactions.push(I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::STATUS],
+                vec![status | BF::DATA_READY],
+            ));
+            transactions.push(I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::RAW_DATA],
+                raw_data.to_vec(),
+            ));
+        }
+        None => {
+            for _ in 0..20 {
+                transactions.push(I2cTrans::write_read(
+                    DEV_ADDR,
+                    vec![Register::STATUS],
+                    vec![status],
+                ));
+            }
+        }
+    }
+    transactions.extend(vec![
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 0]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::ERROR_ID], vec![error_id]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::BASELINE], vec![0x34, 0x52]),
+        I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::BASELINE],
+            baseline_readback.to_vec(),
+        ),
+    ]);
+    transactions
+}
+
+const HEALTHY: u8 = BF::FW_MODE | BF::APP_VALID;
+
+#[test]
+fn healthy_device_passes() {
+    let transactions = self_test_transactions(0x81, HEALTHY, Some([0xF4, 41]), 0, [0x34, 0x52]);
+    let mut sensor = new_awake_app(&transactions);
+    let report = sensor.self_test(&mut NoDelay::new()).unwrap();
+    assert!(report.passed());
+    assert_eq!(10, report.raw_current);
+    assert_eq!(0x1F4, report.raw_voltage);
+    destroy_awake(sensor);
+}
+
+#[test]
+fn does_not_record_configuration() {
+    let transactions = self_test_transactions(0x81, HEALTHY, Some([0xF4, 41]), 0, [0x34, 0x52]);
+    let mut sensor = new_awake_app(&transactions);
+    sensor.self_test(&mut NoDelay::new()).unwrap();
+    assert_eq!(None, sensor.applied_config());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn reports_wrong_identity() {
+    let transactions = self_test_transactions(0x80, HEALTHY, Some([0xF4, 41]), 0, [0x34, 0x52]);
+    let mut sensor = new_awake_app(&transactions);
+    let report = sensor.self_test(&mut NoDelay::new()).unwrap();
+    assert!(!report.identity);
+    assert!(!report.passed());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn reports_missing_data() {
+    let transactions = self_test_transactions(0x81, HEALTHY, None, 0, [0x34, 0x52]);
+    let mut sensor = new_awake_app(&transactions);
+    let report = sensor.self_test(&mut NoDelay::new()).unwrap();
+    assert!(!report.measurement);
+    assert!(!report.raw_data);
+    assert!(report.identity && report.valid_app && report.heater && report.baseline);
+    destroy_awake(sensor);
+}
+
+#[test]
+fn reports_implausible_raw_data() {
+    let transactions = self_test_transactions(0x81, HEALTHY, Some([0, 0]), 0, [0x34, 0x52]);
+    let mut sensor = new_awake_app(&transactions);
+    let report = sensor.self_test(&mut NoDelay::new()).unwrap();
+    assert!(report.measurement);
+    assert!(!report.raw_data);
+    destroy_awake(sensor);
+}
+
+#[test]
+fn reports_heater_and_resistance_errors() {
+    let transactions = self_test_transactions(
+        0x81,
+        HEALTHY | BF::ERROR,
+        Some([0xF4, 41]),
+        BF::HEATER_SUPPLY | BF::MAX_RESISTANCE,
+        [0x34, 0x52],
+    );
+    let mut sensor = new_awake_app(&transactions);
+    let report = sensor.self_test(&mut NoDelay::new()).unwrap();
+    assert!(!report.heater);
+    assert!(!report.resistance);
+    assert!(report.errors.heater_supply);
+    assert!(report.errors.max_resistance);
+    destroy_awake(sensor);
+}
+
+#[test]
+fn reports_baseline_mismatch() {
+    let transactions = self_test_transactions(0x81, HEALTHY, Some([0xF4, 41]), 0, [0, 0]);
+    let mut sensor = new_awake_app(&transactions);
+    let report = sensor.self_test(&mut NoDelay::new()).unwrap();
+    assert!(!report.baseline);
+    assert!(!report.passed());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn refuses_to_run_in_slower_mode() {
+    let mut sensor = new_awake_app(&write(vec![Register::MEAS_MODE, 2 << 4]));
+    sensor.set_mode(MeasurementMode::PulseHeating10s).unwrap();
+    assert_error!(
+        sensor.self_test(&mut NoDelay::new()),
+        ErrorAwake::InvalidInputData
+    );
+    destroy_awake(sensor);
+}
+
+#[test]
+fn restores_mode_after_failed_measurement() {
+    let mut transactions = write(vec![Register::MEAS_MODE, 1 << 4]);
+    transactions.extend(vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0x81]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_VERSION], vec![0x12]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![HEALTHY]),
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::STATUS],
+            vec![HEALTHY | BF::DATA_READY],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::RAW_DATA], vec![0, 0])
+            .with_error(ErrorKind::Bus),
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+    ]);
+    let mut sensor = new_awake_app(&transactions);
+    sensor.set_mode(MeasurementMode::ConstantPower1s).unwrap();
+    match sensor.self_test(&mut NoDelay::new()) {
+        Err(ErrorAwake::I2C(_)) => (),
+        _ => panic!("Wrong result"),
+    }
+    destroy_awake(sensor);
+}
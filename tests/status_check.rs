@@ -0,0 +1,217 @@
+use embedded_ccs811::{
+    mode, prelude::*, Ccs811Awake, ErrorAwake, InterruptMode, MeasurementMode, StatusCheck,
+};
+use embedded_hal_mock::eh1::i2c::{Mock as I2cMock, Transaction as I2cTrans};
+mod common;
+use crate::common::{destroy_awake, new_awake, BitFlags as BF, Register, DEV_ADDR};
+
+fn start_transactions() -> Vec<I2cTrans> {
+    vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+    ]
+}
+
+fn new_awake_app(
+    policy: StatusCheck,
+    transactions: &[I2cTrans],
+) -> Ccs811Awake<I2cMock, mode::App> {
+    let mut all = start_transactions();
+    all.extend_from_slice(transactions);
+    let mut sensor = new_awake(&all);
+    sensor.set_status_check(StatusCheck::Deferred);
+    let mut sensor = sensor.start_application().ok().unwrap();
+    sensor.set_status_check(policy);
+    sensor
+}
+
+#[test]
+fn default_policy_is_always() {
+    let sensor = new_awake(&[]);
+    assert_eq!(StatusCheck::Always, sensor.status_check());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn policy_is_kept_on_mode_change() {
+    let mut sensor = new_awake(&start_transactions());
+    sensor.set_status_check(StatusCheck::Never);
+    let sensor = sensor.start_application().ok().unwrap();
+    assert_eq!(StatusCheck::Never, sensor.status_check());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn always_checks_status_after_write() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Always,
+        &[
+            I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+        ],
+    );
+    sensor.set_baseline([0x34, 0x52]).unwrap();
+    destroy_awake(sensor);
+}
+
+#[test]
+fn detects_reset_after_write() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Always,
+        &[
+            I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        ],
+    );
+    assert_error!(
+        sensor.set_mode(MeasurementMode::ConstantPower1s),
+        ErrorAwake::UnexpectedReset
+    );
+    destroy_awake(sensor);
+}
+
+#[test]
+fn deferred_skips_status_after_read_and_write() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Deferred,
+        &[
+            I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::BASELINE], vec![0x34, 0x52]),
+        ],
+    );
+    sensor.set_baseline([0x34, 0x52]).unwrap();
+    assert_eq!([0x34, 0x52], sensor.baseline().unwrap());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn deferred_reports_errors_on_next_status_read() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Deferred,
+        &[
+            I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 4 << 4]),
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::STATUS],
+                vec![BF::FW_MODE | BF::ERROR],
+            ),
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::ERROR_ID],
+                vec![BF::MEASMODE_INVALID],
+            ),
+        ],
+    );
+    sensor.set_mode(MeasurementMode::ConstantPower250ms).ok();
+    match sensor.has_data_ready() {
+        Err(ErrorAwake::Device(errors)) => assert!(errors.invalid_measurement),
+        _ => panic!("Wrong result"),
+    }
+    destroy_awake(sensor);
+}
+
+#[test]
+fn never_does_not_read_error_id() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Never,
+        &[I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::STATUS],
+            vec![BF::FW_MODE | BF::ERROR | BF::DATA_READY],
+        )],
+    );
+    assert!(sensor.has_data_ready().unwrap());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn never_reports_errors_in_alg_result_data() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Never,
+        &[I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::ALG_RESULT_DATA],
+            vec![0, 0, 0, 0, BF::FW_MODE | BF::ERROR, BF::HEATER_FAULT, 0, 0],
+        )],
+    );
+    match sensor.data() {
+        Err(nb::Error::Other(ErrorAwake::Device(errors))) => assert!(errors.heater_fault),
+        _ => panic!("Wrong result"),
+    }
+    destroy_awake(sensor);
+}
+
+#[test]
+fn batch_checks_status_once() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Always,
+        &[
+            I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+            I2cTrans::write(
+                DEV_ADDR,
+                vec![Register::MEAS_MODE, (1 << 4) | BF::INTERRUPT],
+            ),
+            I2cTrans::write(DEV_ADDR, vec![Register::THRESHOLDS, 0x05, 0xDC, 0x09, 0xC4]),
+            I2cTrans::write(DEV_ADDR, vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]),
+            I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+        ],
+    );
+    sensor
+        .batch(|s| {
+            s.set_mode(MeasurementMode::ConstantPower1s)?;
+            s.set_interrupt_mode(InterruptMode::OnDataReady)?;
+            s.set_eco2_thresholds(1500, 2500)?;
+            s.set_environment(48.25, 25.125)?;
+            s.set_baseline([0x34, 0x52])
+        })
+        .unwrap();
+    assert_eq!(StatusCheck::Always, sensor.status_check());
+    destroy_awake(sensor);
+}
+
+#[test]
+fn batch_detects_reset() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Always,
+        &[
+            I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+            I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+            I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        ],
+    );
+    assert_error!(
+        sensor.batch(|s| {
+            s.set_mode(MeasurementMode::ConstantPower1s)?;
+            s.set_baseline([0x34, 0x52])
+        }),
+        ErrorAwake::UnexpectedReset
+    );
+    destroy_awake(sensor);
+}
+
+#[test]
+fn batch_reports_errors_even_if_never_checking() {
+    let mut sensor = new_awake_app(
+        StatusCheck::Never,
+        &[
+            I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::STATUS],
+                vec![BF::FW_MODE | BF::ERROR],
+            ),
+            I2cTrans::write_read(
+                DEV_ADDR,
+                vec![Register::ERROR_ID],
+                vec![BF::WRITE_REG_INVALID],
+            ),
+        ],
+    );
+    match sensor.batch(|s| s.set_baseline([0x34, 0x52])) {
+        Err(ErrorAwake::Device(errors)) => assert!(errors.invalid_register_write),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(StatusCheck::Never, sensor.status_check());
+    destroy_awake(sensor);
+}
@@ -0,0 +1,165 @@
+use embedded_ccs811::{
+    mode, prelude::*, Ccs811Awake, Ccs811Config, Ccs811Supervisor, ErrorAwake, InterruptMode,
+    MeasurementMode, SupervisorError,
+};
+use embedded_hal_mock::eh1::{
+    delay::NoopDelay as NoDelay,
+    i2c::{Mock as I2cMock, Transaction as I2cTrans},
+};
+mod common;
+use crate::common::{
+    alg_result, destroy_awake, new_awake_app, write, BitFlags as BF, Register, DEV_ADDR,
+};
+
+fn new_supervisor(
+    transactions: &[I2cTrans],
+) -> Ccs811Supervisor<Ccs811Awake<I2cMock, mode::App>, NoDelay> {
+    Ccs811Supervisor::new(new_awake_app(transactions), NoDelay::new())
+}
+
+fn destroy_supervisor(supervisor: Ccs811Supervisor<Ccs811Awake<I2cMock, mode::App>, NoDelay>) {
+    destroy_awake(supervisor.destroy().0);
+}
+
+#[test]
+fn passes_data_through() {
+    let mut supervisor = new_supervisor(&[alg_result(0, 0, BF::FW_MODE | BF::DATA_READY)]);
+    supervisor.data().unwrap();
+    assert_eq!(0, supervisor.recoveries());
+    destroy_supervisor(supervisor);
+}
+
+#[test]
+fn restores_configuration_after_reset() {
+    let mut transactions = write(vec![Register::MEAS_MODE, 1 << 4]);
+    transactions.extend(write(vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]));
+    transactions.extend(write(vec![Register::BASELINE, 0x34, 0x52]));
+    transactions.push(alg_result(0, 0, BF::APP_VALID));
+    // recovery
+    transactions.push(I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::STATUS],
+        vec![BF::APP_VALID],
+    ));
+    transactions.extend(write(vec![Register::APP_START]));
+    transactions.extend(write(vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]));
+    transactions.extend(write(vec![Register::MEAS_MODE, 1 << 4]));
+    transactions.extend(write(vec![Register::BASELINE, 0x34, 0x52]));
+    let mut supervisor = new_supervisor(&transactions);
+    supervisor.apply(&Ccs811Config::default()).unwrap();
+    supervisor.set_environment(48.25, 25.125).unwrap();
+    supervisor.set_baseline([0x34, 0x52]).unwrap();
+    match supervisor.data() {
+        Err(nb::Error::Other(SupervisorError::Recovered)) => (),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(1, supervisor.recoveries());
+    let config = supervisor.device().applied_config().unwrap();
+    assert_eq!(Some((48.25, 25.125)), config.environment);
+    assert_eq!(Some([0x34, 0x52]), config.baseline);
+    destroy_supervisor(supervisor);
+}
+
+#[test]
+fn restores_settings_made_without_apply() {
+    let mut transactions = write(vec![Register::MEAS_MODE, 2 << 4]);
+    transactions.extend(write(vec![Register::MEAS_MODE, (2 << 4) | BF::INTERRUPT]));
+    transactions.extend(write(vec![Register::THRESHOLDS, 0x05, 0xDC, 0x09, 0xC4]));
+    transactions.extend(write(vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]));
+    transactions.extend(write(vec![Register::BASELINE, 0x34, 0x52]));
+    transactions.push(I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::STATUS],
+        vec![BF::APP_VALID],
+    ));
+    // recovery
+    transactions.push(I2cTrans::write_read(
+        DEV_ADDR,
+        vec![Register::STATUS],
+        vec![BF::APP_VALID],
+    ));
+    transactions.extend(write(vec![Register::APP_START]));
+    transactions.extend(write(vec![Register::THRESHOLDS, 0x05, 0xDC, 0x09, 0xC4]));
+    transactions.extend(write(vec![Register::ENV_DATA, 0x60, 0x80, 0x64, 0x40]));
+    transactions.extend(write(vec![Register::MEAS_MODE, (2 << 4) | BF::INTERRUPT]));
+    transactions.extend(write(vec![Register::BASELINE, 0x34, 0x52]));
+    let mut supervisor = new_supervisor(&transactions);
+    supervisor
+        .set_mode(MeasurementMode::PulseHeating10s)
+        .unwrap();
+    supervisor
+        .set_interrupt_mode(InterruptMode::OnDataReady)
+        .unwrap();
+    supervisor.set_eco2_thresholds(1500, 2500).unwrap();
+    supervisor.set_environment(48.25, 25.125).unwrap();
+    supervisor.set_baseline([0x34, 0x52]).unwrap();
+    match supervisor.has_data_ready() {
+        Err(SupervisorError::Recovered) => (),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(1, supervisor.recoveries());
+    destroy_supervisor(supervisor);
+}
+
+#[test]
+fn reports_reset_without_configuration() {
+    let mut transactions = vec![
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        // recovery
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+    ];
+    transactions.extend(write(vec![Register::APP_START]));
+    let mut supervisor = new_supervisor(&transactions);
+    match supervisor.has_data_ready() {
+        Err(SupervisorError::Unconfigured) => (),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(1, supervisor.recoveries());
+    destroy_supervisor(supervisor);
+}
+
+#[test]
+fn sets_again_after_reset_on_write() {
+    let mut transactions = write(vec![Register::MEAS_MODE, 1 << 4]);
+    transactions.extend(vec![
+        I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        // recovery
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+    ]);
+    transactions.extend(write(vec![Register::APP_START]));
+    transactions.extend(write(vec![Register::MEAS_MODE, 1 << 4]));
+    transactions.extend(write(vec![Register::BASELINE, 0x34, 0x52]));
+    let mut supervisor = new_supervisor(&transactions);
+    supervisor.apply(&Ccs811Config::default()).unwrap();
+    match supervisor.set_baseline([0x34, 0x52]) {
+        Err(SupervisorError::Recovered) => (),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(1, supervisor.recoveries());
+    destroy_supervisor(supervisor);
+}
+
+#[test]
+fn reports_failed_recovery() {
+    let mut supervisor = new_supervisor(&[
+        alg_result(0, 0, 0),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+    ]);
+    match supervisor.data() {
+        Err(nb::Error::Other(SupervisorError::RecoveryFailed(ErrorAwake::NoValidApp))) => (),
+        _ => panic!("Wrong result"),
+    }
+    destroy_supervisor(supervisor);
+}
+
+#[test]
+fn passes_other_errors_through() {
+    let mut supervisor = new_supervisor(&[]);
+    match supervisor.set_environment(101.0, 25.0) {
+        Err(SupervisorError::Error(ErrorAwake::InvalidInputData)) => (),
+        _ => panic!("Wrong result"),
+    }
+    assert_eq!(0, supervisor.recoveries());
+    destroy_supervisor(supervisor);
+}
@@ -0,0 +1,78 @@
+use core::cell::Cell;
+use embedded_ccs811::{prelude::*, MeasurementMode, TimedResult};
+mod common;
+use crate::common::{
+    alg_result, destroy, destroy_awake, new, new_awake_app, result, start_application, wake_cycles,
+    write, BitFlags as BF, Register,
+};
+use nb::Error as NbError;
+
+#[test]
+fn stamps_results() {
+    let mut sensor = new_awake_app(&[
+        alg_result(400, 16, BF::FW_MODE | BF::DATA_READY),
+        alg_result(400, 16, BF::FW_MODE),
+        alg_result(400, 16, BF::FW_MODE | BF::DATA_READY),
+    ]);
+    let now = Cell::new(1000);
+    let clock = || now.get();
+    assert_eq!(
+        TimedResult {
+            result: result(400, 16),
+            timestamp_ms: 1000,
+            since_previous_ms: None,
+        },
+        sensor.data_timed(&clock).unwrap()
+    );
+    now.set(1500);
+    match sensor.data_timed(&clock) {
+        Err(NbError::WouldBlock) => (),
+        _ => panic!("Would block not returned."),
+    }
+    now.set(2100);
+    assert_eq!(
+        TimedResult {
+            result: result(400, 16),
+            timestamp_ms: 2100,
+            since_previous_ms: Some(1100),
+        },
+        sensor.data_timed(&clock).unwrap()
+    );
+    destroy_awake(sensor);
+}
+
+#[test]
+fn stamps_results_across_clock_wrap() {
+    let mut transactions = start_application();
+    transactions.extend([
+        alg_result(400, 16, BF::FW_MODE | BF::DATA_READY),
+        alg_result(400, 16, BF::FW_MODE | BF::DATA_READY),
+    ]);
+    let mut sensor = new(&transactions, wake_cycles(3))
+        .start_application()
+        .ok()
+        .unwrap();
+    let now = Cell::new(u32::MAX - 499);
+    let clock = || now.get();
+    sensor.data_timed(&clock).unwrap();
+    now.set(500);
+    let timed = sensor.data_timed(&clock).unwrap();
+    assert_eq!(Some(1000), timed.since_previous_ms);
+    destroy(sensor);
+}
+
+#[test]
+fn setting_mode_resets_elapsed_time() {
+    let mut transactions = vec![alg_result(400, 16, BF::FW_MODE | BF::DATA_READY)];
+    transactions.extend(write(vec![Register::MEAS_MODE, 1 << 4]));
+    transactions.push(alg_result(400, 16, BF::FW_MODE | BF::DATA_READY));
+    let mut sensor = new_awake_app(&transactions);
+    let now = Cell::new(1000);
+    let clock = || now.get();
+    sensor.data_timed(&clock).unwrap();
+    sensor.set_mode(MeasurementMode::ConstantPower1s).unwrap();
+    now.set(2000);
+    let timed = sensor.data_timed(&clock).unwrap();
+    assert_eq!(None, timed.since_previous_ms);
+    destroy_awake(sensor);
+}
@@ -0,0 +1,110 @@
+use embedded_ccs811::{
+    Hysteresis, PwmFan, VentilationConfig, VentilationController, VentilationOverride,
+};
+use embedded_hal_mock::eh1::{
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    pwm::{Mock as PwmMock, Transaction as PwmTrans},
+};
+mod common;
+use crate::common::result;
+
+fn config() -> VentilationConfig {
+    VentilationConfig {
+        eco2: Some(Hysteresis { on: 1000, off: 800 }),
+        etvoc: Some(Hysteresis { on: 500, off: 250 }),
+        min_on_ms: 10_000,
+        min_off_ms: 5_000,
+    }
+}
+
+#[test]
+fn writes_initial_state_on_first_update() {
+    let pin = PinMock::new(&[PinTrans::set(PinState::Low)]);
+    let mut controller = VentilationController::new(pin, config());
+    assert!(!controller.update(&result(500, 50), 0).unwrap());
+    assert!(!controller.update(&result(600, 50), 1000).unwrap());
+    controller.destroy().done();
+}
+
+#[test]
+fn switches_with_hysteresis() {
+    let pin = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+    ]);
+    let mut controller = VentilationController::new(pin, config());
+    assert!(!controller.update(&result(999, 50), 0).unwrap());
+    assert!(controller.update(&result(1000, 50), 10_000).unwrap());
+    assert!(controller.update(&result(900, 50), 30_000).unwrap());
+    assert!(controller.update(&result(799, 300), 40_000).unwrap());
+    assert!(!controller.update(&result(799, 249), 50_000).unwrap());
+    controller.destroy().done();
+}
+
+#[test]
+fn switches_on_etvoc() {
+    let pin = PinMock::new(&[PinTrans::set(PinState::High)]);
+    let mut controller = VentilationController::new(pin, config());
+    assert!(controller.update(&result(450, 500), 0).unwrap());
+    controller.destroy().done();
+}
+
+#[test]
+fn respects_minimum_on_and_off_times() {
+    let pin = PinMock::new(&[
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let mut controller = VentilationController::new(pin, config());
+    assert!(controller.update(&result(1200, 50), 0).unwrap());
+    assert!(controller.update(&result(450, 50), 9_999).unwrap());
+    assert!(!controller.update(&result(450, 50), 10_000).unwrap());
+    assert!(!controller.update(&result(1200, 50), 14_999).unwrap());
+    assert!(controller.update(&result(1200, 50), 15_000).unwrap());
+    controller.destroy().done();
+}
+
+#[test]
+fn override_takes_effect_immediately() {
+    let pin = PinMock::new(&[
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let mut controller = VentilationController::new(pin, config());
+    assert!(controller.set_override(VentilationOverride::On, 0).unwrap());
+    assert!(controller.update(&result(450, 50), 1000).unwrap());
+    assert!(!controller
+        .set_override(VentilationOverride::Off, 2000)
+        .unwrap());
+    assert!(!controller.update(&result(2000, 50), 3000).unwrap());
+    assert!(!controller
+        .set_override(VentilationOverride::Auto, 4000)
+        .unwrap());
+    assert_eq!(VentilationOverride::Auto, controller.override_mode());
+    assert!(!controller.update(&result(2000, 50), 6000).unwrap());
+    assert!(controller.update(&result(2000, 50), 7000).unwrap());
+    controller.destroy().done();
+}
+
+#[test]
+fn can_drive_pwm_fan() {
+    let pwm = PwmMock::new(&[
+        PwmTrans::max_duty_cycle(100),
+        PwmTrans::set_duty_cycle(60),
+        PwmTrans::set_duty_cycle(0),
+    ]);
+    let fan = PwmFan::new(pwm, 60);
+    let mut controller = VentilationController::new(
+        fan,
+        VentilationConfig {
+            min_on_ms: 0,
+            ..config()
+        },
+    );
+    assert!(controller.update(&result(1500, 50), 0).unwrap());
+    assert!(!controller.update(&result(450, 50), 1000).unwrap());
+    controller.destroy().destroy().done();
+}
@@ -0,0 +1,81 @@
+use embedded_ccs811::{prelude::*, Error, ErrorAwake, InterruptMode, MeasurementMode};
+use embedded_hal_mock::eh1::{
+    digital::{Mock as PinMock, State as PinState, Transaction as PinTrans},
+    i2c::Transaction as I2cTrans,
+};
+mod common;
+use crate::common::{destroy, new, BitFlags as BF, Register, DEV_ADDR};
+
+#[test]
+fn can_run_several_operations_in_one_wake_cycle() {
+    let nwake = PinMock::new(&[
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+        PinTrans::set(PinState::Low),
+        PinTrans::set(PinState::High),
+    ]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::APP_VALID]),
+        I2cTrans::write(DEV_ADDR, vec![Register::APP_START]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+        // started
+        I2cTrans::write(DEV_ADDR, vec![Register::MEAS_MODE, 1 << 4]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+        I2cTrans::write(
+            DEV_ADDR,
+            vec![Register::MEAS_MODE, (1 << 4) | BF::INTERRUPT],
+        ),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+        I2cTrans::write(DEV_ADDR, vec![Register::BASELINE, 0x34, 0x52]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::FW_MODE]),
+    ];
+    let mut sensor = new(&transactions, nwake).start_application().ok().unwrap();
+    sensor
+        .with_awake(|dev| {
+            dev.set_mode(MeasurementMode::ConstantPower1s)?;
+            dev.set_interrupt_mode(InterruptMode::OnDataReady)?;
+            dev.set_baseline([0x34, 0x52])
+        })
+        .unwrap();
+    destroy(sensor);
+}
+
+#[test]
+fn can_batch_operations_in_one_wake_cycle() {
+    let nwake = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0x81]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_VERSION], vec![0x12]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![0]),
+    ];
+    let mut sensor = new(&transactions, nwake);
+    let ids = sensor
+        .with_awake(|dev| dev.batch(|dev| Ok((dev.hardware_id()?, dev.hardware_version()?))))
+        .unwrap();
+    assert_eq!((0x81, (1, 2)), ids);
+    destroy(sensor);
+}
+
+#[test]
+fn releases_nwake_on_error() {
+    let nwake = PinMock::new(&[PinTrans::set(PinState::Low), PinTrans::set(PinState::High)]);
+    let transactions = [
+        I2cTrans::write_read(DEV_ADDR, vec![Register::HW_ID], vec![0x81]),
+        I2cTrans::write_read(DEV_ADDR, vec![Register::STATUS], vec![BF::ERROR]),
+        I2cTrans::write_read(
+            DEV_ADDR,
+            vec![Register::ERROR_ID],
+            vec![BF::READ_REG_INVALID],
+        ),
+    ];
+    let mut sensor = new(&transactions, nwake);
+    let result = sensor.with_awake(|dev| {
+        dev.hardware_id()?;
+        Err::<(), _>(ErrorAwake::InvalidInputData)
+    });
+    match result {
+        Err(Error::Device(errors)) => assert!(errors.invalid_register_read),
+        _ => panic!("Wrong result"),
+    }
+    destroy(sensor);
+}